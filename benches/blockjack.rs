@@ -1,45 +1,96 @@
+use std::env;
+use std::sync::OnceLock;
 use std::time::Duration;
 
-use blockjack::{naive_game::NaiveGame, secure_game::SecureGame};
+use blockjack::naive_game::NaiveGameState;
+use blockjack::scenario::{scenarios, Scenario};
 use blockjack::tfhe_keys::initialize_keys;
-use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use tfhe::ClientKey;
 
-pub fn blockjack(criterion: &mut Criterion) {
+fn load_scenarios(client_key: &ClientKey) -> &'static [Scenario] {
+    static SCENARIOS: OnceLock<Vec<Scenario>> = OnceLock::new();
+
+    SCENARIOS.get_or_init(|| {
+        let scenarios = match env::var("BLOCKJACK_SCENARIOS") {
+            Ok(path) => Scenario::load(&path).unwrap_or_else(|error| panic!("{}: {}", path, error)),
+            Err(_) => scenarios(),
+        };
+
+        for scenario in &scenarios {
+            let steps = scenario.actions.len();
+            let secure_state = scenario.secure_game(client_key, steps).state();
+
+            assert_eq!(
+                scenario.outcome,
+                scenario.naive_game(steps).state(),
+                "{}",
+                scenario.name
+            );
+            assert_eq!(
+                scenario.outcome,
+                NaiveGameState::from(secure_state),
+                "{}",
+                scenario.name
+            );
+        }
+
+        scenarios
+    })
+}
+
+pub fn actions(criterion: &mut Criterion) {
     let (client_key, _) = initialize_keys();
 
-    let mut group = criterion.benchmark_group("blockjack");
+    let mut group = criterion.benchmark_group("actions");
 
-    group.bench_function(BenchmarkId::new("playNaive", 0), |bench| {
-        bench.iter(|| {
-            let mut game = NaiveGame::new(0);
+    for scenario in load_scenarios(&client_key) {
+        for (step, &action) in scenario.actions.iter().enumerate() {
+            let parameter = format!("{}/{}", scenario.name, step);
 
-            let deck = vec![6, 6, 6, 6, 6, 6];
+            group.bench_function(
+                BenchmarkId::new(format!("naive{:?}", action), &parameter),
+                |bench| {
+                    bench.iter_batched(
+                        || scenario.naive_game(step),
+                        |mut game| game.play(action).unwrap(),
+                        BatchSize::PerIteration,
+                    )
+                },
+            );
 
-            game.plant_deck(&deck);
-            game.create_game();
-            game.hit_as_player();
-            game.stand();
-            game.hit_as_dealer();
+            group.bench_function(
+                BenchmarkId::new(format!("secure{:?}", action), &parameter),
+                |bench| {
+                    bench.iter_batched(
+                        || scenario.secure_game(&client_key, step),
+                        |mut game| game.play(action).unwrap(),
+                        BatchSize::PerIteration,
+                    )
+                },
+            );
+        }
+    }
 
-            game.dump_game();
-        })
-    });
+    group.finish();
+}
 
-    group.bench_function(BenchmarkId::new("playSecure", 0), |bench| {
-        bench.iter(|| {
-            let mut game = SecureGame::new(&client_key);
+pub fn blockjack(criterion: &mut Criterion) {
+    let (client_key, _) = initialize_keys();
+
+    let mut group = criterion.benchmark_group("blockjack");
 
-            let deck = vec![6, 6, 6, 6, 6, 6];
+    for scenario in load_scenarios(&client_key) {
+        let steps = scenario.actions.len();
 
-            game.plant_deck(&deck);
-            game.create_game();
-            game.hit_as_player();
-            game.stand();
-            game.hit_as_dealer();
+        group.bench_function(BenchmarkId::new("playNaive", &scenario.name), |bench| {
+            bench.iter(|| scenario.naive_game(steps))
+        });
 
-            game.dump_game();
-        })
-    });
+        group.bench_function(BenchmarkId::new("playSecure", &scenario.name), |bench| {
+            bench.iter(|| scenario.secure_game(&client_key, steps))
+        });
+    }
 
     group.finish();
 }
@@ -47,7 +98,7 @@ pub fn blockjack(criterion: &mut Criterion) {
 criterion_group! {
     name = benches;
     config = Criterion::default().measurement_time(Duration::from_secs(120)).sample_size(10);
-    targets = blockjack
+    targets = actions, blockjack
 }
 
 criterion_main!(benches);
@@ -0,0 +1,61 @@
+use std::time::Duration;
+
+use blockjack::secure_game::SecureGame;
+use blockjack::tfhe_keys::initialize_keys;
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+
+pub fn parallel(criterion: &mut Criterion) {
+    let (client_key, server_key) = initialize_keys();
+
+    let deck = vec![6, 6, 6, 6, 6, 6];
+
+    let mut group = criterion.benchmark_group("parallel");
+
+    for parallel in [false, true] {
+        let mode = if parallel { "parallel" } else { "sequential" };
+
+        let new_game = || {
+            let mut game = SecureGame::new(&client_key);
+
+            if parallel {
+                game.parallelize(&server_key);
+            }
+
+            game.plant_deck(&deck);
+
+            game
+        };
+
+        group.bench_function(BenchmarkId::new("createGame", mode), |bench| {
+            bench.iter_batched(
+                new_game,
+                |mut game| game.create_game(),
+                BatchSize::PerIteration,
+            )
+        });
+
+        group.bench_function(BenchmarkId::new("stand", mode), |bench| {
+            bench.iter_batched(
+                || {
+                    let mut game = new_game();
+
+                    game.create_game();
+
+                    game
+                },
+                |mut game| game.stand(),
+                BatchSize::PerIteration,
+            )
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default().measurement_time(Duration::from_secs(60)).sample_size(10);
+    targets = parallel
+}
+
+criterion_main!(benches);
@@ -0,0 +1,118 @@
+use std::time::Duration;
+
+use blockjack::secure_game::{SecureGame, SecureGameState};
+use blockjack::secure_hand::SecureHand;
+use blockjack::tfhe_keys::initialize_keys;
+use blockjack::tfhe_values::{
+    encrypt_card, encrypt_points, generate_card, rate_card, trivial_points, TrivialStates,
+};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use tfhe::prelude::{DivRem, FheEq, FheOrd, IfThenElse};
+use tfhe::{FheUint8, Seed};
+
+pub fn primitives(criterion: &mut Criterion) {
+    let (client_key, _) = initialize_keys();
+
+    let card = encrypt_card(&client_key, 13);
+    let cards: Vec<_> = (2..=11)
+        .map(|card| encrypt_card(&client_key, card))
+        .collect();
+    let constants = TrivialStates::default();
+
+    let mut hand = SecureHand::new();
+
+    hand.push(&cards[8]);
+    hand.push(&cards[6]);
+
+    let points = hand.rate_cards();
+
+    let mut full_hand = SecureHand::new();
+
+    for card in &cards {
+        full_hand.push(card);
+    }
+
+    let random = FheUint8::generate_oblivious_pseudo_random(Seed(0));
+
+    let mut game = SecureGame::new(&client_key);
+
+    game.plant_deck(&[6, 6, 6, 6]);
+    game.create_game();
+
+    let state = game.encrypted_state().unwrap();
+
+    let mut group = criterion.benchmark_group("primitives");
+
+    group.bench_function(BenchmarkId::new("encryptCard", 0), |bench| {
+        bench.iter(|| encrypt_card(&client_key, 13))
+    });
+
+    group.bench_function(BenchmarkId::new("encryptPoints", 0), |bench| {
+        bench.iter(|| encrypt_points(&client_key, 21))
+    });
+
+    group.bench_function(BenchmarkId::new("rateCard", 0), |bench| {
+        bench.iter(|| rate_card(&card))
+    });
+
+    for count in 2..=10 {
+        group.bench_function(BenchmarkId::new("rateCards", count), |bench| {
+            bench.iter(|| {
+                full_hand.cards()[..count]
+                    .iter()
+                    .map(rate_card)
+                    .fold(trivial_points(0), |total, points| total + points)
+            })
+        });
+    }
+
+    group.bench_function(BenchmarkId::new("eq", 21), |bench| {
+        bench.iter(|| points.eq(21))
+    });
+
+    group.bench_function(BenchmarkId::new("gt", 21), |bench| {
+        bench.iter(|| points.gt(21))
+    });
+
+    group.bench_function(BenchmarkId::new("lt", 17), |bench| {
+        bench.iter(|| points.lt(17))
+    });
+
+    group.bench_function(BenchmarkId::new("select", 0), |bench| {
+        let condition = points.eq(21);
+
+        bench.iter(|| {
+            condition.select(
+                constants.get(SecureGameState::PlayerWins),
+                constants.get(SecureGameState::WaitingForPlayer),
+            )
+        })
+    });
+
+    group.bench_function(
+        BenchmarkId::new("generateObliviousPseudoRandom", 0),
+        |bench| bench.iter(|| FheUint8::generate_oblivious_pseudo_random(Seed(0))),
+    );
+
+    group.bench_function(BenchmarkId::new("divRem", 52), |bench| {
+        bench.iter(|| FheUint8::div_rem(random.clone(), 52))
+    });
+
+    group.bench_function(BenchmarkId::new("generateCard", 0), |bench| {
+        bench.iter(|| generate_card(0))
+    });
+
+    group.bench_function(BenchmarkId::new("decryptState", 0), |bench| {
+        bench.iter(|| state.decrypt(&client_key))
+    });
+
+    group.finish();
+}
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default().measurement_time(Duration::from_secs(30)).sample_size(10);
+    targets = primitives
+}
+
+criterion_main!(benches);
@@ -0,0 +1,93 @@
+use std::time::Duration;
+
+use blockjack::secure_hand::{SecureHand, HAND_CAPACITY};
+use blockjack::tfhe_keys::initialize_keys;
+use blockjack::tfhe_values::{
+    encrypt_card, rate_card, rate_card_by_comparison, trivial_card, trivial_points, FheCard,
+    FhePoints,
+};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use tfhe::prelude::{FheEq, FheOrd, IfThenElse};
+
+fn rescore(cards: &[FheCard], count: &FheCard) -> FhePoints {
+    let zero = trivial_points(0);
+
+    cards
+        .iter()
+        .enumerate()
+        .fold(zero.clone(), |total, (index, card)| {
+            total
+                + count
+                    .gt(index as u8)
+                    .select(&rate_card_by_comparison(card), &zero)
+        })
+}
+
+pub fn scoring(criterion: &mut Criterion) {
+    let (client_key, _) = initialize_keys();
+
+    let card = encrypt_card(&client_key, 13);
+    let cards: Vec<FheCard> = [6, 6, 6, 6]
+        .iter()
+        .map(|&card_value| encrypt_card(&client_key, card_value))
+        .collect();
+
+    let mut group = criterion.benchmark_group("scoring");
+
+    group.bench_function(BenchmarkId::new("rateCardByComparison", 0), |bench| {
+        bench.iter(|| rate_card_by_comparison(&card))
+    });
+
+    group.bench_function(BenchmarkId::new("rateCardByLookupTable", 0), |bench| {
+        bench.iter(|| rate_card(&card))
+    });
+
+    group.bench_function(
+        BenchmarkId::new("scoreHandByRescoring", cards.len()),
+        |bench| {
+            bench.iter(|| {
+                let mut slots: Vec<FheCard> = (0..HAND_CAPACITY).map(|_| trivial_card(0)).collect();
+                let mut count = trivial_card(0);
+                let mut points = trivial_points(0);
+
+                for card in &cards {
+                    for (index, slot) in slots.iter_mut().enumerate() {
+                        *slot = count.eq(index as u8).select(card, slot);
+                    }
+
+                    count += 1;
+                    points = rescore(&slots, &count);
+                }
+
+                points
+            })
+        },
+    );
+
+    group.bench_function(
+        BenchmarkId::new("scoreHandByRunningTotal", cards.len()),
+        |bench| {
+            bench.iter(|| {
+                let mut hand = SecureHand::new();
+                let mut points = trivial_points(0);
+
+                for card in &cards {
+                    hand.push(card);
+                    points = hand.rate_cards();
+                }
+
+                points
+            })
+        },
+    );
+
+    group.finish();
+}
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default().measurement_time(Duration::from_secs(60)).sample_size(10);
+    targets = scoring
+}
+
+criterion_main!(benches);
@@ -0,0 +1,586 @@
+use crate::game_rules::RuleSet;
+use crate::game_transcript::GameAction;
+use crate::naive_game::NaiveGame;
+use crate::playing_card::rank;
+use crate::secure_hand::SecureHand;
+use crate::strategy::Strategy;
+use crate::tfhe_values::{rate_card, FheCard};
+
+use std::collections::{BTreeMap, HashMap};
+
+use tfhe::prelude::{CastFrom, FheEq, IfThenElse};
+use tfhe::{FheUint16, FheUint8, MatchValues};
+
+const BUST: usize = 22;
+const INFINITE_WEIGHTS: [u16; 10] = [1, 1, 1, 1, 1, 1, 1, 1, 4, 1];
+const RANKS: [u8; 10] = [2, 3, 4, 5, 6, 7, 8, 9, 10, 14];
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Advice {
+    Hit = 0,
+    Stand = 1,
+    Double = 2,
+    Split = 3,
+}
+
+impl TryFrom<u8> for Advice {
+    type Error = u8;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Advice::Hit),
+            1 => Ok(Advice::Stand),
+            2 => Ok(Advice::Double),
+            3 => Ok(Advice::Split),
+            _ => Err(value),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Eq, Hash, PartialEq)]
+struct Shoe {
+    counts: [u16; 10],
+    infinite: bool,
+}
+
+impl Shoe {
+    fn new(rules: &RuleSet) -> Self {
+        match rules.decks {
+            Some(decks) => Self {
+                counts: INFINITE_WEIGHTS.map(|weight| weight * 4 * decks as u16),
+                infinite: false,
+            },
+            None => Self {
+                counts: INFINITE_WEIGHTS,
+                infinite: true,
+            },
+        }
+    }
+
+    fn draw(&self, index: usize) -> Option<(f64, Shoe)> {
+        let count = self.counts[index];
+
+        if count == 0 {
+            return None;
+        }
+
+        let total: u16 = self.counts.iter().sum();
+
+        Some((count as f64 / total as f64, self.remove_index(index)))
+    }
+
+    fn remove(&self, card: u8) -> Self {
+        self.remove_index(card_index(card))
+    }
+
+    fn remove_index(&self, index: usize) -> Self {
+        let mut shoe = *self;
+
+        if !shoe.infinite {
+            shoe.counts[index] -= 1;
+        }
+
+        shoe
+    }
+}
+
+#[derive(Clone, Copy, Default, Eq, Hash, PartialEq)]
+struct Hand {
+    points: u8,
+    soft_aces: u8,
+}
+
+impl Hand {
+    fn add(self, rules: &RuleSet, card: u8) -> Self {
+        let mut points = self.points + rules.rate_card(card);
+        let mut soft_aces = self.soft_aces + (rules.soft_aces && rank(card) == 14) as u8;
+
+        while points > 21 && soft_aces > 0 {
+            points -= 10;
+            soft_aces -= 1;
+        }
+
+        Self { points, soft_aces }
+    }
+
+    fn from_cards(rules: &RuleSet, cards: &[u8]) -> Self {
+        cards
+            .iter()
+            .fold(Self::default(), |hand, &card| hand.add(rules, card))
+    }
+
+    fn is_bust(&self) -> bool {
+        self.points > 21
+    }
+}
+
+struct Analysis {
+    dealer: HashMap<(Shoe, Hand), [f64; 23]>,
+    player: HashMap<(Shoe, Hand, u8), f64>,
+    rules: RuleSet,
+}
+
+impl Analysis {
+    fn new(rules: &RuleSet) -> Self {
+        Self {
+            dealer: HashMap::new(),
+            player: HashMap::new(),
+            rules: *rules,
+        }
+    }
+
+    fn advise(&mut self, cards_for_player: &[u8], dealer_up_card: u8, allow_split: bool) -> Advice {
+        let shoe = cards_for_player
+            .iter()
+            .chain([&dealer_up_card])
+            .fold(Shoe::new(&self.rules), |shoe, &card| shoe.remove(card));
+        let hand = Hand::from_cards(&self.rules, cards_for_player);
+
+        let mut options = vec![
+            (Advice::Stand, self.stand_value(shoe, hand, dealer_up_card)),
+            (Advice::Hit, self.hit_value(shoe, hand, dealer_up_card)),
+        ];
+
+        if cards_for_player.len() == 2 {
+            if self.rules.double_down {
+                options.push((
+                    Advice::Double,
+                    self.double_value(shoe, hand, dealer_up_card),
+                ));
+            }
+
+            let first = cards_for_player[0];
+
+            if allow_split
+                && self.rules.split_pairs
+                && self.rules.rate_card(first) == self.rules.rate_card(cards_for_player[1])
+            {
+                options.push((Advice::Split, self.split_value(shoe, first, dealer_up_card)));
+            }
+        }
+
+        options
+            .into_iter()
+            .fold((Advice::Stand, f64::MIN), |best, option| {
+                if option.1 > best.1 {
+                    option
+                } else {
+                    best
+                }
+            })
+            .0
+    }
+
+    fn best_value(&mut self, shoe: Shoe, hand: Hand, dealer_up_card: u8) -> f64 {
+        if let Some(&value) = self.player.get(&(shoe, hand, dealer_up_card)) {
+            return value;
+        }
+
+        let value = self
+            .stand_value(shoe, hand, dealer_up_card)
+            .max(self.hit_value(shoe, hand, dealer_up_card));
+
+        self.player.insert((shoe, hand, dealer_up_card), value);
+
+        value
+    }
+
+    fn dealer_outcomes(&mut self, shoe: Shoe, hand: Hand) -> [f64; 23] {
+        if let Some(outcomes) = self.dealer.get(&(shoe, hand)) {
+            return *outcomes;
+        }
+
+        let mut outcomes = [0.0; 23];
+
+        if hand.is_bust() {
+            outcomes[BUST] = 1.0;
+        } else if !self.rules.dealer_must_hit(hand.points, hand.soft_aces > 0) {
+            outcomes[hand.points as usize] = 1.0;
+        } else {
+            for (index, &card) in RANKS.iter().enumerate() {
+                if let Some((probability, next)) = shoe.draw(index) {
+                    let next_outcomes = self.dealer_outcomes(next, hand.add(&self.rules, card));
+
+                    for (outcome, next_outcome) in outcomes.iter_mut().zip(next_outcomes) {
+                        *outcome += probability * next_outcome;
+                    }
+                }
+            }
+        }
+
+        self.dealer.insert((shoe, hand), outcomes);
+
+        outcomes
+    }
+
+    fn dealer_final(&mut self, shoe: Shoe, dealer_up_card: u8) -> [f64; 23] {
+        let up_hand = Hand::default().add(&self.rules, dealer_up_card);
+
+        let mut outcomes = [0.0; 23];
+        let mut weight = 0.0;
+
+        for (index, &card) in RANKS.iter().enumerate() {
+            if let Some((probability, next)) = shoe.draw(index) {
+                let hand = up_hand.add(&self.rules, card);
+
+                if hand.points >= 21 {
+                    continue;
+                }
+
+                weight += probability;
+
+                for (outcome, next_outcome) in
+                    outcomes.iter_mut().zip(self.dealer_outcomes(next, hand))
+                {
+                    *outcome += probability * next_outcome;
+                }
+            }
+        }
+
+        outcomes.map(|outcome| outcome / weight)
+    }
+
+    fn double_value(&mut self, shoe: Shoe, hand: Hand, dealer_up_card: u8) -> f64 {
+        2.0 * self.draw_value(shoe, hand, |analysis, next, hand| {
+            analysis.stand_value(next, hand, dealer_up_card)
+        })
+    }
+
+    fn draw_value(
+        &mut self,
+        shoe: Shoe,
+        hand: Hand,
+        value: impl Fn(&mut Self, Shoe, Hand) -> f64,
+    ) -> f64 {
+        let mut total = 0.0;
+
+        for (index, &card) in RANKS.iter().enumerate() {
+            if let Some((probability, next)) = shoe.draw(index) {
+                let hand = hand.add(&self.rules, card);
+
+                total += probability
+                    * if hand.is_bust() {
+                        -1.0
+                    } else {
+                        value(self, next, hand)
+                    };
+            }
+        }
+
+        total
+    }
+
+    fn hit_value(&mut self, shoe: Shoe, hand: Hand, dealer_up_card: u8) -> f64 {
+        self.draw_value(shoe, hand, |analysis, next, hand| {
+            analysis.best_value(next, hand, dealer_up_card)
+        })
+    }
+
+    fn split_value(&mut self, shoe: Shoe, card: u8, dealer_up_card: u8) -> f64 {
+        let hand = Hand::default().add(&self.rules, card);
+
+        2.0 * self.draw_value(shoe, hand, |analysis, next, hand| {
+            analysis.best_value(next, hand, dealer_up_card)
+        })
+    }
+
+    fn stand_value(&mut self, shoe: Shoe, hand: Hand, dealer_up_card: u8) -> f64 {
+        let outcomes = self.dealer_final(shoe, dealer_up_card);
+
+        outcomes
+            .iter()
+            .enumerate()
+            .map(|(points, probability)| {
+                probability
+                    * if points == BUST || points < hand.points as usize {
+                        1.0
+                    } else if points > hand.points as usize {
+                        -1.0
+                    } else {
+                        0.0
+                    }
+            })
+            .sum()
+    }
+}
+
+pub struct StrategyTable {
+    hard: BTreeMap<u8, [Advice; 10]>,
+    pairs: BTreeMap<u8, [Advice; 10]>,
+    rules: RuleSet,
+    soft: BTreeMap<u8, [Advice; 10]>,
+}
+
+impl StrategyTable {
+    pub fn advise_encrypted(
+        &self,
+        cards_for_player: &SecureHand,
+        dealer_up_card: &FheCard,
+    ) -> FheUint8 {
+        assert!(
+            !self.rules.soft_aces,
+            "Soft aces are not supported by the secure engine"
+        );
+
+        let up = FheUint16::cast_from(rate_card(dealer_up_card));
+        let points = FheUint16::cast_from(cards_for_player.rate_cards());
+        let index = points * 16u16 + &up;
+
+        let advice: FheUint8 = index
+            .match_value_or(&table_matches(&self.hard, false), Advice::Stand as u16)
+            .unwrap();
+        let initial_advice: FheUint8 = index
+            .match_value_or(&table_matches(&self.hard, true), Advice::Stand as u16)
+            .unwrap();
+
+        let initial_advice = if self.pairs.is_empty() {
+            initial_advice
+        } else {
+            let first = rate_card(&cards_for_player.cards()[0]);
+            let second = rate_card(&cards_for_player.cards()[1]);
+
+            let (pair_advice, matched): (FheUint8, _) =
+                (FheUint16::cast_from(first.clone()) * 16u16 + &up)
+                    .match_value(&table_matches(&self.pairs, true))
+                    .unwrap();
+
+            (first.eq(&second) & matched).select(&pair_advice, &initial_advice)
+        };
+
+        cards_for_player
+            .count()
+            .eq(2)
+            .select(&initial_advice, &advice)
+    }
+
+    pub fn advise_game(&self, game: &NaiveGame) -> Advice {
+        self.lookup(game.cards_for_player(), game.cards_for_dealer()[0])
+    }
+
+    pub fn generate(rules: &RuleSet) -> Self {
+        let mut analysis = Analysis::new(rules);
+
+        let mut row = |cards: [u8; 2], allow_split: bool| {
+            RANKS.map(|up_card| analysis.advise(&cards, up_card, allow_split))
+        };
+
+        let hard = (4..=20)
+            .map(|points| {
+                let cards = if points <= 11 {
+                    [2, points - 2]
+                } else {
+                    [10, points - 10]
+                };
+
+                (points, row(cards, false))
+            })
+            .collect();
+
+        let soft = if rules.soft_aces {
+            (2..=9)
+                .map(|card| (card + 11, row([14, card], false)))
+                .collect()
+        } else {
+            BTreeMap::new()
+        };
+
+        let pairs = if rules.split_pairs {
+            (2..=11)
+                .filter(|&value| rules.soft_aces || value != 11)
+                .map(|value| {
+                    let card = if value == 11 { 14 } else { value };
+
+                    (value, row([card, card], true))
+                })
+                .collect()
+        } else {
+            BTreeMap::new()
+        };
+
+        Self {
+            hard,
+            pairs,
+            rules: *rules,
+            soft,
+        }
+    }
+
+    pub fn hard(&self) -> &BTreeMap<u8, [Advice; 10]> {
+        &self.hard
+    }
+
+    pub fn lookup(&self, cards_for_player: &[u8], dealer_up_card: u8) -> Advice {
+        let column = card_index(dealer_up_card);
+
+        if cards_for_player.len() == 2 {
+            let first = self.rules.rate_card(cards_for_player[0]);
+
+            if first == self.rules.rate_card(cards_for_player[1]) {
+                if let Some(row) = self.pairs.get(&first) {
+                    return row[column];
+                }
+            }
+        }
+
+        self.lookup_total(cards_for_player, column)
+    }
+
+    pub fn pairs(&self) -> &BTreeMap<u8, [Advice; 10]> {
+        &self.pairs
+    }
+
+    pub fn soft(&self) -> &BTreeMap<u8, [Advice; 10]> {
+        &self.soft
+    }
+
+    fn lookup_total(&self, cards_for_player: &[u8], column: usize) -> Advice {
+        let (points, soft) = self.rules.rate_hand(cards_for_player);
+
+        let row = if soft {
+            self.soft.get(&points)
+        } else {
+            self.hard.get(&points)
+        };
+
+        match row.map(|row| row[column]) {
+            Some(Advice::Double) if cards_for_player.len() > 2 => Advice::Hit,
+            Some(advice) => advice,
+            None if points < 12 => Advice::Hit,
+            None => Advice::Stand,
+        }
+    }
+}
+
+impl Strategy for StrategyTable {
+    fn decide(&self, _rules: &RuleSet, cards_for_player: &[u8], dealer_up_card: u8) -> GameAction {
+        let advice = match self.lookup(cards_for_player, dealer_up_card) {
+            Advice::Split => self.lookup_total(cards_for_player, card_index(dealer_up_card)),
+            advice => advice,
+        };
+
+        match advice {
+            Advice::Stand => GameAction::Stand,
+            _ => GameAction::HitAsPlayer,
+        }
+    }
+}
+
+pub fn advise(rules: &RuleSet, cards_for_player: &[u8], dealer_up_card: u8) -> Advice {
+    Analysis::new(rules).advise(cards_for_player, dealer_up_card, true)
+}
+
+fn card_index(card: u8) -> usize {
+    match rank(card) {
+        2..=10 => rank(card) as usize - 2,
+        11..=13 => 8,
+        _ => 9,
+    }
+}
+
+fn table_matches(table: &BTreeMap<u8, [Advice; 10]>, initial: bool) -> MatchValues<u16> {
+    let matches = table
+        .iter()
+        .flat_map(|(&points, row)| {
+            row.iter().zip(RANKS).map(move |(&advice, up_card)| {
+                let advice = if advice == Advice::Double && !initial {
+                    Advice::Hit
+                } else {
+                    advice
+                };
+                let up_points = if up_card == 14 { 11 } else { up_card };
+
+                (points as u16 * 16 + up_points as u16, advice as u16)
+            })
+        })
+        .collect();
+
+    MatchValues::new(matches).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::tfhe_keys::initialize_keys;
+    use crate::tfhe_values::{encrypt_card, trivial_card};
+
+    use tfhe::prelude::FheDecrypt;
+
+    const A: u8 = 14;
+
+    #[test]
+    fn advise_from_shoe_composition() {
+        let rules = RuleSet {
+            decks: Some(1),
+            ..RuleSet::default()
+        };
+
+        assert_eq!(Advice::Stand, advise(&rules, &[10, 10], 6));
+        assert_eq!(Advice::Stand, advise(&rules, &[10, 6], 10));
+        assert_eq!(Advice::Hit, advise(&rules, &[2, 3], 10));
+    }
+
+    #[test]
+    fn advise_playable_actions_only() {
+        let rules = RuleSet {
+            soft_aces: true,
+            ..RuleSet::default()
+        };
+        let table_rules = RuleSet {
+            double_down: true,
+            split_pairs: true,
+            ..rules
+        };
+
+        assert_eq!(Advice::Hit, advise(&rules, &[6, 5], 6));
+        assert_eq!(Advice::Hit, advise(&rules, &[8, 8], 10));
+        assert_eq!(Advice::Double, advise(&table_rules, &[6, 5], 6));
+        assert_eq!(Advice::Split, advise(&table_rules, &[8, 8], 10));
+        assert!(StrategyTable::generate(&rules).pairs().is_empty());
+    }
+
+    #[test]
+    fn advise_encrypted_cards() {
+        let (client_key, _) = initialize_keys();
+
+        let table = StrategyTable::generate(&RuleSet::default());
+
+        let mut cards_for_player = SecureHand::new();
+
+        cards_for_player.push(&encrypt_card(&client_key, 10));
+        cards_for_player.push(&encrypt_card(&client_key, 6));
+
+        let advice: u8 = table
+            .advise_encrypted(&cards_for_player, &trivial_card(10))
+            .decrypt(&client_key);
+
+        assert_eq!(
+            table.lookup(&[10, 6], 10),
+            Advice::try_from(advice).unwrap()
+        );
+    }
+
+    #[test]
+    fn generate_table() {
+        let rules = RuleSet {
+            double_down: true,
+            soft_aces: true,
+            split_pairs: true,
+            ..RuleSet::default()
+        };
+
+        let table = StrategyTable::generate(&rules);
+
+        assert!(table.hard()[&5].iter().all(|&advice| advice == Advice::Hit));
+        assert!(table.hard()[&17]
+            .iter()
+            .all(|&advice| advice == Advice::Stand));
+        assert!(table.hard()[&20]
+            .iter()
+            .all(|&advice| advice == Advice::Stand));
+        assert_eq!(Advice::Hit, table.lookup(&[10, 6], 10));
+        assert_eq!(Advice::Split, table.lookup(&[A, A], 6));
+        assert_eq!(Advice::Stand, table.lookup(&[10, 10], 6));
+        assert_eq!(Advice::Stand, table.lookup(&[A, 9], 6));
+    }
+}
@@ -0,0 +1,134 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use serde::Serialize;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize)]
+pub struct AllocationStats {
+    pub allocated_bytes: usize,
+    pub allocations: usize,
+    pub peak_bytes: usize,
+}
+
+pub struct TrackingAllocator {
+    allocated_bytes: AtomicUsize,
+    allocations: AtomicUsize,
+    current_bytes: AtomicUsize,
+    peak_bytes: AtomicUsize,
+}
+
+unsafe impl GlobalAlloc for TrackingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let pointer = System.alloc(layout);
+
+        if !pointer.is_null() {
+            self.record_allocation(layout.size());
+        }
+
+        pointer
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let pointer = System.alloc_zeroed(layout);
+
+        if !pointer.is_null() {
+            self.record_allocation(layout.size());
+        }
+
+        pointer
+    }
+
+    unsafe fn dealloc(&self, pointer: *mut u8, layout: Layout) {
+        System.dealloc(pointer, layout);
+
+        self.current_bytes
+            .fetch_sub(layout.size(), Ordering::Relaxed);
+    }
+
+    unsafe fn realloc(&self, pointer: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_pointer = System.realloc(pointer, layout, new_size);
+
+        if !new_pointer.is_null() {
+            self.current_bytes
+                .fetch_sub(layout.size(), Ordering::Relaxed);
+            self.record_allocation(new_size);
+        }
+
+        new_pointer
+    }
+}
+
+impl Default for TrackingAllocator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TrackingAllocator {
+    pub fn measure(&self, run: impl FnOnce()) -> AllocationStats {
+        let allocated_bytes = self.allocated_bytes.load(Ordering::Relaxed);
+        let allocations = self.allocations.load(Ordering::Relaxed);
+        let current_bytes = self.current_bytes.load(Ordering::Relaxed);
+
+        self.peak_bytes.store(current_bytes, Ordering::Relaxed);
+
+        run();
+
+        AllocationStats {
+            allocated_bytes: self.allocated_bytes.load(Ordering::Relaxed) - allocated_bytes,
+            allocations: self.allocations.load(Ordering::Relaxed) - allocations,
+            peak_bytes: self
+                .peak_bytes
+                .load(Ordering::Relaxed)
+                .saturating_sub(current_bytes),
+        }
+    }
+
+    pub const fn new() -> Self {
+        Self {
+            allocated_bytes: AtomicUsize::new(0),
+            allocations: AtomicUsize::new(0),
+            current_bytes: AtomicUsize::new(0),
+            peak_bytes: AtomicUsize::new(0),
+        }
+    }
+
+    fn record_allocation(&self, size: usize) {
+        let current_bytes = self.current_bytes.fetch_add(size, Ordering::Relaxed) + size;
+
+        self.allocated_bytes.fetch_add(size, Ordering::Relaxed);
+        self.allocations.fetch_add(1, Ordering::Relaxed);
+        self.peak_bytes.fetch_max(current_bytes, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn track_allocations() {
+        let allocator = TrackingAllocator::new();
+
+        let stats = allocator.measure(|| unsafe {
+            let small = Layout::from_size_align(100, 8).unwrap();
+            let large = Layout::from_size_align(400, 8).unwrap();
+
+            let first = allocator.alloc(small);
+            allocator.dealloc(first, small);
+
+            let second = allocator.alloc(large);
+            let second = allocator.realloc(second, large, 200);
+            allocator.dealloc(second, Layout::from_size_align(200, 8).unwrap());
+        });
+
+        assert_eq!(
+            AllocationStats {
+                allocated_bytes: 700,
+                allocations: 3,
+                peak_bytes: 400,
+            },
+            stats
+        );
+    }
+}
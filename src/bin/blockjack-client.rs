@@ -0,0 +1,39 @@
+use std::env;
+
+use blockjack::game_rules::RuleSet;
+use blockjack::game_transcript::GameAction;
+use blockjack::secure_client::{HandResult, SecureClient};
+use blockjack::secure_game::SecureGameState;
+use blockjack::secure_protocol::DEFAULT_ADDRESS;
+use blockjack::tfhe_keys::initialize_keys;
+
+fn dump_result(action: GameAction, result: &HandResult) {
+    println!(
+        "{:?}: player {:?}, dealer {:?}, {:?}",
+        action, result.cards_for_player, result.cards_for_dealer, result.state
+    );
+}
+
+fn main() {
+    let address = env::args()
+        .nth(1)
+        .unwrap_or_else(|| DEFAULT_ADDRESS.to_string());
+
+    let (client_key, server_key) = initialize_keys();
+
+    let mut client = SecureClient::connect(&client_key, &address).unwrap();
+
+    client.register_key(&server_key).unwrap();
+
+    let mut result = client.new_hand().unwrap();
+
+    dump_result(GameAction::CreateGame, &result);
+
+    while result.state == SecureGameState::WaitingForPlayer {
+        let hit = RuleSet::default().rate_cards(&result.cards_for_player) < 17;
+
+        result = client.decide(hit).unwrap();
+
+        dump_result(GameAction::Decide, &result);
+    }
+}
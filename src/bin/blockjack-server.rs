@@ -0,0 +1,26 @@
+use std::env;
+use std::net::TcpListener;
+use std::thread;
+
+use blockjack::secure_protocol::DEFAULT_ADDRESS;
+use blockjack::secure_server::serve_client;
+
+fn main() {
+    let address = env::args()
+        .nth(1)
+        .unwrap_or_else(|| DEFAULT_ADDRESS.to_string());
+
+    let listener = TcpListener::bind(&address).unwrap();
+
+    println!("Listening on {}", address);
+
+    for stream in listener.incoming() {
+        let stream = stream.unwrap();
+
+        thread::spawn(move || {
+            if let Err(error) = serve_client(stream) {
+                eprintln!("Connection failed: {}", error);
+            }
+        });
+    }
+}
@@ -0,0 +1,172 @@
+use crate::playing_card::rank;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BetSpread {
+    pub max_bet: f64,
+    pub min_bet: f64,
+    pub units_per_count: f64,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct CardCounter {
+    cards_seen: usize,
+    decks: u8,
+    running_count: i32,
+    system: CountingSystem,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CountingSystem {
+    HiLo,
+    Ko,
+    OmegaII,
+}
+
+impl BetSpread {
+    pub fn bet(&self, count: f64) -> f64 {
+        (self.min_bet + self.units_per_count * (count.floor() - 1.0))
+            .clamp(self.min_bet, self.max_bet)
+    }
+
+    pub fn flat(bet: f64) -> Self {
+        Self {
+            max_bet: bet,
+            min_bet: bet,
+            units_per_count: 0.0,
+        }
+    }
+}
+
+impl Default for BetSpread {
+    fn default() -> Self {
+        Self {
+            max_bet: 8.0,
+            min_bet: 1.0,
+            units_per_count: 2.0,
+        }
+    }
+}
+
+impl CardCounter {
+    pub fn betting_count(&self) -> f64 {
+        if self.system.is_balanced() {
+            self.true_count()
+        } else {
+            self.running_count as f64
+        }
+    }
+
+    pub fn cards_seen(&self) -> usize {
+        self.cards_seen
+    }
+
+    pub fn decks(&self) -> u8 {
+        self.decks
+    }
+
+    pub fn decks_remaining(&self) -> f64 {
+        let cards_remaining = (self.decks as usize * 52).saturating_sub(self.cards_seen);
+
+        (cards_remaining as f64 / 52.0).max(0.5)
+    }
+
+    pub fn new(system: CountingSystem, decks: u8) -> Self {
+        Self {
+            cards_seen: 0,
+            decks,
+            running_count: system.initial_running_count(decks),
+            system,
+        }
+    }
+
+    pub fn observe(&mut self, card: u8) {
+        self.cards_seen += 1;
+        self.running_count += self.system.tag(card);
+    }
+
+    pub fn observe_all(&mut self, cards: &[u8]) {
+        for &card in cards {
+            self.observe(card);
+        }
+    }
+
+    pub fn reset(&mut self) {
+        *self = Self::new(self.system, self.decks);
+    }
+
+    pub fn running_count(&self) -> i32 {
+        self.running_count
+    }
+
+    pub fn system(&self) -> CountingSystem {
+        self.system
+    }
+
+    pub fn true_count(&self) -> f64 {
+        self.running_count as f64 / self.decks_remaining()
+    }
+}
+
+impl CountingSystem {
+    pub fn initial_running_count(&self, decks: u8) -> i32 {
+        match self {
+            CountingSystem::Ko => 4 - 4 * decks as i32,
+            _ => 0,
+        }
+    }
+
+    pub fn is_balanced(&self) -> bool {
+        *self != CountingSystem::Ko
+    }
+
+    pub fn tag(&self, card: u8) -> i32 {
+        match (self, rank(card)) {
+            (CountingSystem::HiLo, 2..=6) => 1,
+            (CountingSystem::HiLo, 7..=9) => 0,
+            (CountingSystem::HiLo, _) => -1,
+            (CountingSystem::Ko, 2..=7) => 1,
+            (CountingSystem::Ko, 8..=9) => 0,
+            (CountingSystem::Ko, _) => -1,
+            (CountingSystem::OmegaII, 2 | 3 | 7) => 1,
+            (CountingSystem::OmegaII, 4..=6) => 2,
+            (CountingSystem::OmegaII, 8 | 14) => 0,
+            (CountingSystem::OmegaII, 9) => -1,
+            (CountingSystem::OmegaII, _) => -2,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn count_cards() {
+        let mut counter = CardCounter::new(CountingSystem::HiLo, 2);
+
+        counter.observe_all(&[2, 5, 6, 9, 10, 14, 3, 4]);
+
+        assert_eq!(3, counter.running_count());
+        assert_eq!(3.0 / (96.0 / 52.0), counter.true_count());
+
+        let full_deck: Vec<u8> = (0..4).flat_map(|_| 2..=14).collect();
+
+        for system in [
+            CountingSystem::HiLo,
+            CountingSystem::Ko,
+            CountingSystem::OmegaII,
+        ] {
+            let mut counter = CardCounter::new(system, 1);
+
+            counter.observe_all(&full_deck);
+
+            let expected = if system.is_balanced() { 0 } else { 4 };
+
+            assert_eq!(expected, counter.running_count());
+        }
+
+        assert_eq!(1.0, BetSpread::default().bet(-3.0));
+        assert_eq!(5.0, BetSpread::default().bet(3.4));
+        assert_eq!(8.0, BetSpread::default().bet(9.0));
+    }
+}
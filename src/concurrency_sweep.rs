@@ -0,0 +1,228 @@
+use crate::allocation_tracker::AllocationStats;
+
+use std::io::{Result, Write};
+use std::time::Duration;
+
+use serde::Serialize;
+
+const CSV_HEADER: &str = "engine,scenario,threads,iterations,games,hands,elapsed_seconds,hands_per_second,latency_p50_ms,latency_p90_ms,latency_p99_ms,latency_max_ms,allocated_bytes,allocations,peak_bytes,game_allocated_bytes,game_allocations,game_peak_bytes";
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize)]
+pub struct LatencyPercentiles {
+    pub max_ms: f64,
+    pub p50_ms: f64,
+    pub p90_ms: f64,
+    pub p99_ms: f64,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct LevelReport {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allocations: Option<AllocationStats>,
+    pub elapsed_seconds: f64,
+    pub games: usize,
+    pub hands: usize,
+    pub hands_per_second: f64,
+    pub latency: LatencyPercentiles,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct SweepReport {
+    pub levels: Vec<LevelReport>,
+    pub summary: SweepSummary,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct SweepSummary {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allocations_per_game: Option<AllocationStats>,
+    pub deck: Vec<u8>,
+    pub elapsed_seconds: f64,
+    pub engine: String,
+    pub hands: usize,
+    pub hands_per_second: f64,
+    pub iterations: usize,
+    pub latency: LatencyPercentiles,
+    pub scenario: String,
+    pub threads: usize,
+}
+
+impl LatencyPercentiles {
+    pub fn new(latencies: &[Duration]) -> Self {
+        let mut milliseconds: Vec<f64> = latencies
+            .iter()
+            .map(|latency| latency.as_secs_f64() * 1000.0)
+            .collect();
+
+        milliseconds.sort_by(f64::total_cmp);
+
+        let percentile = |percent: usize| {
+            let rank = (percent * milliseconds.len()).div_ceil(100).max(1);
+
+            milliseconds.get(rank - 1).copied().unwrap_or_default()
+        };
+
+        Self {
+            max_ms: milliseconds.last().copied().unwrap_or_default(),
+            p50_ms: percentile(50),
+            p90_ms: percentile(90),
+            p99_ms: percentile(99),
+        }
+    }
+
+    fn to_csv(self) -> String {
+        format!(
+            "{:.3},{:.3},{:.3},{:.3}",
+            self.p50_ms, self.p90_ms, self.p99_ms, self.max_ms
+        )
+    }
+}
+
+impl LevelReport {
+    pub fn new(
+        games: usize,
+        elapsed: Duration,
+        latencies: &[Duration],
+        allocations: Option<AllocationStats>,
+    ) -> Self {
+        Self {
+            allocations,
+            elapsed_seconds: elapsed.as_secs_f64(),
+            games,
+            hands: latencies.len(),
+            hands_per_second: latencies.len() as f64 / elapsed.as_secs_f64(),
+            latency: LatencyPercentiles::new(latencies),
+        }
+    }
+
+    fn to_csv(&self, summary: &SweepSummary) -> String {
+        format!(
+            "{},{},{},{:.6},{:.2},{},{},,,",
+            summary.to_csv(),
+            self.games,
+            self.hands,
+            self.elapsed_seconds,
+            self.hands_per_second,
+            self.latency.to_csv(),
+            allocations_to_csv(self.allocations)
+        )
+    }
+}
+
+impl SweepSummary {
+    pub fn new(
+        engine: &str,
+        scenario: &str,
+        deck: &[u8],
+        iterations: usize,
+        threads: usize,
+        elapsed: Duration,
+        latencies: &[Duration],
+    ) -> Self {
+        Self {
+            allocations_per_game: None,
+            deck: deck.to_vec(),
+            elapsed_seconds: elapsed.as_secs_f64(),
+            engine: engine.to_string(),
+            hands: latencies.len(),
+            hands_per_second: latencies.len() as f64 / elapsed.as_secs_f64(),
+            iterations,
+            latency: LatencyPercentiles::new(latencies),
+            scenario: scenario.to_string(),
+            threads,
+        }
+    }
+
+    fn to_csv(&self) -> String {
+        format!(
+            "{},{},{},{}",
+            self.engine, self.scenario, self.threads, self.iterations
+        )
+    }
+}
+
+fn allocations_to_csv(allocations: Option<AllocationStats>) -> String {
+    allocations
+        .map(|allocations| {
+            format!(
+                "{},{},{}",
+                allocations.allocated_bytes, allocations.allocations, allocations.peak_bytes
+            )
+        })
+        .unwrap_or(",,".to_string())
+}
+
+pub fn export_csv(report: &SweepReport, mut writer: impl Write) -> Result<()> {
+    writeln!(writer, "{}", CSV_HEADER)?;
+
+    let summary = &report.summary;
+
+    for level in &report.levels {
+        writeln!(writer, "{}", level.to_csv(summary))?;
+    }
+
+    writeln!(
+        writer,
+        "{},all,{},{:.6},{:.2},{},,,,{}",
+        summary.to_csv(),
+        summary.hands,
+        summary.elapsed_seconds,
+        summary.hands_per_second,
+        summary.latency.to_csv(),
+        allocations_to_csv(summary.allocations_per_game)
+    )
+}
+
+pub fn export_json(report: &SweepReport, writer: impl Write) -> Result<()> {
+    serde_json::to_writer_pretty(writer, report)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn report_levels() {
+        let latencies: Vec<Duration> = (1..=100).rev().map(Duration::from_millis).collect();
+
+        assert_eq!(
+            LatencyPercentiles {
+                max_ms: 100.0,
+                p50_ms: 50.0,
+                p90_ms: 90.0,
+                p99_ms: 99.0,
+            },
+            LatencyPercentiles::new(&latencies)
+        );
+        assert_eq!(LatencyPercentiles::default(), LatencyPercentiles::new(&[]));
+
+        let elapsed = Duration::from_secs(4);
+        let report = SweepReport {
+            levels: vec![LevelReport::new(10, elapsed, &latencies[..20], None)],
+            summary: SweepSummary {
+                allocations_per_game: Some(AllocationStats {
+                    allocated_bytes: 700,
+                    allocations: 3,
+                    peak_bytes: 400,
+                }),
+                ..SweepSummary::new("naive", "tie", &[6], 2, 1, elapsed, &latencies[..20])
+            },
+        };
+
+        let mut csv = Vec::new();
+
+        export_csv(&report, &mut csv).unwrap();
+
+        assert_eq!(
+            format!(
+                "{}\n{}\n{}\n",
+                CSV_HEADER,
+                "naive,tie,1,2,10,20,4.000000,5.00,90.000,98.000,100.000,100.000,,,,,,",
+                "naive,tie,1,2,all,20,4.000000,5.00,90.000,98.000,100.000,100.000,,,,700,3,400"
+            ),
+            String::from_utf8(csv).unwrap()
+        );
+    }
+}
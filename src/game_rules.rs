@@ -0,0 +1,90 @@
+use crate::playing_card::{rank, PlayingCard, SUITS};
+
+use rand::Rng;
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+pub struct RuleSet {
+    pub dealer_hits_soft_17: bool,
+    pub dealer_stands_on: u8,
+    pub decks: Option<u8>,
+    pub double_down: bool,
+    pub natural_pays: f64,
+    pub soft_aces: bool,
+    pub split_pairs: bool,
+}
+
+impl Default for RuleSet {
+    fn default() -> Self {
+        Self {
+            dealer_hits_soft_17: false,
+            dealer_stands_on: 17,
+            decks: None,
+            double_down: false,
+            natural_pays: 1.0,
+            soft_aces: false,
+            split_pairs: false,
+        }
+    }
+}
+
+impl RuleSet {
+    pub fn dealer_must_hit(&self, points: u8, soft: bool) -> bool {
+        points < self.dealer_stands_on || (self.dealer_hits_soft_17 && soft && points == 17)
+    }
+
+    pub fn rate_card(&self, card: u8) -> u8 {
+        let card = rank(card);
+
+        if card < 11 {
+            card
+        } else {
+            if card < 14 {
+                10
+            } else {
+                11
+            }
+        }
+    }
+
+    pub fn rate_cards(&self, cards: &[u8]) -> u8 {
+        self.rate_hand(cards).0
+    }
+
+    pub fn rate_hand(&self, cards: &[u8]) -> (u8, bool) {
+        let mut soft_aces = if self.soft_aces {
+            cards.iter().filter(|&&card| rank(card) == 14).count()
+        } else {
+            0
+        };
+
+        let mut points = cards
+            .iter()
+            .fold(0, |total, &card| total + self.rate_card(card));
+
+        while points > 21 && soft_aces > 0 {
+            points -= 10;
+            soft_aces -= 1;
+        }
+
+        (points, soft_aces > 0)
+    }
+
+    pub fn shuffle_shoe(&self, rng: &mut ChaCha8Rng) -> Option<Vec<u8>> {
+        let decks = self.decks?;
+
+        let mut shoe: Vec<u8> = (0..decks)
+            .flat_map(|_| SUITS)
+            .flat_map(|suit| (2..=14).map(move |rank| PlayingCard::new(rank, suit).encode()))
+            .collect();
+
+        for index in (1..shoe.len()).rev() {
+            let other = rng.random_range(0..=index);
+
+            shoe.swap(index, other);
+        }
+
+        Some(shoe)
+    }
+}
@@ -0,0 +1,28 @@
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+pub const STORAGE_VERSION: u8 = 1;
+
+#[derive(Debug, PartialEq)]
+pub enum StorageError {
+    Corrupt,
+    UnsupportedVersion(u8),
+}
+
+pub fn load_snapshot<T: DeserializeOwned>(buffer: &[u8]) -> Result<T, StorageError> {
+    let (&version, payload) = buffer.split_first().ok_or(StorageError::Corrupt)?;
+
+    if version != STORAGE_VERSION {
+        return Err(StorageError::UnsupportedVersion(version));
+    }
+
+    bincode::deserialize(payload).map_err(|_| StorageError::Corrupt)
+}
+
+pub fn save_snapshot<T: Serialize>(snapshot: &T) -> Vec<u8> {
+    let mut buffer = vec![STORAGE_VERSION];
+
+    bincode::serialize_into(&mut buffer, snapshot).unwrap();
+
+    buffer
+}
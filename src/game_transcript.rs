@@ -0,0 +1,360 @@
+use crate::game_rules::RuleSet;
+use crate::naive_game::{NaiveGame, NaiveGameState};
+use crate::tfhe_values::{deserialize, generate_card, serialize, FheCard};
+
+use serde::{Deserialize, Serialize};
+use sha3::{Digest, Sha3_256};
+use tfhe::prelude::FheDecrypt;
+use tfhe::{ClientKey, FheBool};
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+pub enum GameAction {
+    CreateGame,
+    Decide,
+    HitAsDealer,
+    HitAsPlayer,
+    Stand,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ActionError {
+    DecisionRequired,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum AuditError {
+    CardMismatch(usize),
+    CommitmentMismatch,
+    CorruptCard(usize),
+    CorruptDecision(usize),
+    IllegalAction(usize),
+    SeedMismatch(usize),
+    StateMismatch(usize),
+}
+
+#[derive(Clone, Deserialize, Serialize)]
+pub struct DealtCard {
+    pub ciphertext: Vec<u8>,
+    pub hash: [u8; 32],
+    pub seed: Option<u128>,
+}
+
+#[derive(Clone, Deserialize, Serialize)]
+pub struct TranscriptEntry {
+    pub action: GameAction,
+    pub cards: Vec<DealtCard>,
+    pub decision: Option<Vec<u8>>,
+    pub state: u8,
+}
+
+#[derive(Clone, Deserialize, Serialize)]
+pub struct GameTranscript {
+    commitment: [u8; 32],
+    entries: Vec<TranscriptEntry>,
+    pending: Vec<DealtCard>,
+    rules: RuleSet,
+    seed: u128,
+}
+
+impl GameTranscript {
+    pub fn commitment(&self) -> [u8; 32] {
+        self.commitment
+    }
+
+    pub fn entries(&self) -> &[TranscriptEntry] {
+        &self.entries
+    }
+
+    pub fn new(rules: RuleSet, seed: u128) -> Self {
+        Self {
+            commitment: [0; 32],
+            entries: vec![],
+            pending: vec![],
+            rules,
+            seed,
+        }
+    }
+
+    pub fn outcome(&self) -> Option<u8> {
+        self.entries.last().map(|entry| entry.state)
+    }
+
+    pub fn record_action(&mut self, action: GameAction, state: u8) {
+        self.entries.push(TranscriptEntry {
+            action,
+            cards: self.pending.drain(..).collect(),
+            decision: None,
+            state,
+        });
+    }
+
+    pub fn record_card(&mut self, card: &FheCard, seed: Option<u128>) {
+        let ciphertext = serialize(card);
+
+        self.commitment = chain_hash(&self.commitment, &ciphertext);
+
+        self.pending.push(DealtCard {
+            ciphertext,
+            hash: self.commitment,
+            seed,
+        });
+    }
+
+    pub fn record_decision(&mut self, hit: &FheBool, state: u8) {
+        let decision = serialize(hit);
+
+        self.commitment = chain_hash(&self.commitment, &decision);
+
+        self.entries.push(TranscriptEntry {
+            action: GameAction::Decide,
+            cards: self.pending.drain(..).collect(),
+            decision: Some(decision),
+            state,
+        });
+    }
+
+    pub fn reveal_state(&mut self, state: u8) {
+        if let Some(entry) = self.entries.last_mut() {
+            entry.state = state;
+        }
+    }
+
+    pub fn rules(&self) -> RuleSet {
+        self.rules
+    }
+
+    pub fn seed(&self) -> u128 {
+        self.seed
+    }
+}
+
+pub fn audit_transcript(
+    key: &ClientKey,
+    transcript: &GameTranscript,
+    commitment: &[u8; 32],
+) -> Result<(), AuditError> {
+    let mut deck = Vec::new();
+    let mut head = [0; 32];
+
+    for (step, entry) in transcript.entries.iter().enumerate() {
+        for dealt_card in &entry.cards {
+            head = chain_hash(&head, &dealt_card.ciphertext);
+
+            if head != dealt_card.hash {
+                return Err(AuditError::CardMismatch(step));
+            }
+
+            deck.push(audit_card(key, dealt_card, step)?);
+        }
+
+        if let Some(decision) = &entry.decision {
+            head = chain_hash(&head, decision);
+        }
+    }
+
+    if head != *commitment {
+        return Err(AuditError::CommitmentMismatch);
+    }
+
+    deck.reverse();
+
+    let mut game = NaiveGame::with_rules(0, transcript.rules);
+
+    game.plant_deck(&deck);
+
+    let mut state = game.state() as u8;
+
+    for (step, entry) in transcript.entries.iter().enumerate() {
+        if !is_legal(entry.action, state) {
+            return Err(AuditError::IllegalAction(step));
+        }
+
+        match entry.action {
+            GameAction::Decide => {
+                game.decide(audit_decision(key, entry, step)?);
+                game.play_out_dealer(entry.cards.len().saturating_sub(1));
+            }
+            GameAction::Stand => {
+                game.stand();
+                game.play_out_dealer(entry.cards.len());
+            }
+            GameAction::CreateGame => game.create_game(),
+            GameAction::HitAsDealer => game.hit_as_dealer(),
+            GameAction::HitAsPlayer => game.hit_as_player(),
+        }
+
+        state = game.state() as u8;
+
+        if state != entry.state {
+            return Err(AuditError::StateMismatch(step));
+        }
+    }
+
+    Ok(())
+}
+
+fn audit_card(key: &ClientKey, dealt_card: &DealtCard, step: usize) -> Result<u8, AuditError> {
+    let card: FheCard =
+        deserialize(&dealt_card.ciphertext).map_err(|_| AuditError::CorruptCard(step))?;
+
+    let card_value: u8 = card.decrypt(key);
+
+    if let Some(seed) = dealt_card.seed {
+        let expected_value: u8 = generate_card(seed).decrypt(key);
+
+        if card_value != expected_value {
+            return Err(AuditError::SeedMismatch(step));
+        }
+    }
+
+    Ok(card_value)
+}
+
+fn audit_decision(
+    key: &ClientKey,
+    entry: &TranscriptEntry,
+    step: usize,
+) -> Result<bool, AuditError> {
+    let decision = entry
+        .decision
+        .as_ref()
+        .ok_or(AuditError::CorruptDecision(step))?;
+
+    let hit: FheBool = deserialize(decision).map_err(|_| AuditError::CorruptDecision(step))?;
+
+    Ok(hit.decrypt(key))
+}
+
+fn chain_hash(head: &[u8; 32], ciphertext: &[u8]) -> [u8; 32] {
+    Sha3_256::new()
+        .chain_update(head)
+        .chain_update(ciphertext)
+        .finalize()
+        .into()
+}
+
+pub fn is_legal(action: GameAction, state: u8) -> bool {
+    match action {
+        GameAction::CreateGame => state == NaiveGameState::Uninitialized as u8,
+        GameAction::Decide => state == NaiveGameState::WaitingForPlayer as u8,
+        GameAction::HitAsDealer => state == NaiveGameState::WaitingForDealer as u8,
+        GameAction::HitAsPlayer => state == NaiveGameState::WaitingForPlayer as u8,
+        GameAction::Stand => state == NaiveGameState::WaitingForPlayer as u8,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::secure_game::SecureGame;
+    use crate::tfhe_keys::initialize_keys;
+    use crate::tfhe_values::encrypt_card;
+
+    const A: u8 = 14;
+
+    #[test]
+    fn audit_accepts_honest_game() {
+        let (client_key, _) = initialize_keys();
+
+        let mut game = SecureGame::new(&client_key);
+
+        let deck = vec![8, 7, 6, 5];
+
+        game.plant_deck(&deck);
+        game.create_game();
+        game.stand();
+        game.hit_as_dealer();
+
+        assert_eq!(
+            Ok(()),
+            audit_transcript(
+                &client_key,
+                game.transcript(),
+                &game.transcript().commitment()
+            )
+        );
+    }
+
+    #[test]
+    fn audit_detects_illegal_action() {
+        let (client_key, _) = initialize_keys();
+
+        let mut game = SecureGame::new(&client_key);
+
+        let deck = vec![9, 8, 7, A, A];
+
+        game.plant_deck(&deck);
+        game.create_game();
+        game.hit_as_player();
+
+        assert_eq!(
+            Err(AuditError::IllegalAction(1)),
+            audit_transcript(
+                &client_key,
+                game.transcript(),
+                &game.transcript().commitment()
+            )
+        );
+    }
+
+    #[test]
+    fn audit_detects_rewritten_hashes() {
+        let (client_key, _) = initialize_keys();
+
+        let mut game = SecureGame::new(&client_key);
+
+        let deck = vec![9, 8, 7, 6];
+
+        game.plant_deck(&deck);
+        game.create_game();
+
+        let mut transcript = game.into_transcript();
+        let commitment = transcript.commitment();
+
+        transcript.entries[0].cards[0].ciphertext = serialize(&encrypt_card(&client_key, A));
+
+        let mut head = [0; 32];
+
+        for dealt_card in &mut transcript.entries[0].cards {
+            head = chain_hash(&head, &dealt_card.ciphertext);
+            dealt_card.hash = head;
+        }
+
+        transcript.commitment = head;
+
+        assert_eq!(
+            Err(AuditError::CommitmentMismatch),
+            audit_transcript(&client_key, &transcript, &commitment)
+        );
+    }
+
+    #[test]
+    fn audit_detects_tampered_transcript() {
+        let (client_key, _) = initialize_keys();
+
+        let mut game = SecureGame::new(&client_key);
+
+        let deck = vec![9, 8, 7, 6];
+
+        game.plant_deck(&deck);
+        game.create_game();
+
+        let mut transcript = game.into_transcript();
+        let commitment = transcript.commitment();
+
+        transcript.entries[0].state = NaiveGameState::PlayerWins as u8;
+
+        assert_eq!(
+            Err(AuditError::StateMismatch(0)),
+            audit_transcript(&client_key, &transcript, &commitment)
+        );
+
+        transcript.entries[0].cards[0].ciphertext[0] ^= 1;
+
+        assert_eq!(
+            Err(AuditError::CardMismatch(0)),
+            audit_transcript(&client_key, &transcript, &commitment)
+        );
+    }
+}
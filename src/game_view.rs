@@ -0,0 +1,120 @@
+use crate::game_rules::RuleSet;
+use crate::naive_game::NaiveGameState;
+use crate::playing_card::format_cards;
+
+use std::fmt;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct GameView {
+    pub cards_for_dealer: HandView,
+    pub cards_for_player: HandView,
+    pub state: NaiveGameState,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct HandView {
+    pub cards: Vec<u8>,
+    pub points: u8,
+    pub soft: bool,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct RedactedView {
+    pub cards_for_dealer: usize,
+    pub cards_for_player: usize,
+    pub state: Option<usize>,
+}
+
+impl fmt::Display for GameView {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            formatter,
+            "Player: {}  Dealer: {}  {:?}",
+            self.cards_for_player, self.cards_for_dealer, self.state
+        )
+    }
+}
+
+impl GameView {
+    pub fn new(
+        rules: &RuleSet,
+        cards_for_player: &[u8],
+        cards_for_dealer: &[u8],
+        state: NaiveGameState,
+    ) -> Self {
+        Self {
+            cards_for_dealer: HandView::new(rules, cards_for_dealer),
+            cards_for_player: HandView::new(rules, cards_for_player),
+            state,
+        }
+    }
+}
+
+impl fmt::Display for HandView {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        let hardness = if self.soft { "soft" } else { "hard" };
+
+        write!(
+            formatter,
+            "{} ({} {})",
+            format_cards(&self.cards),
+            self.points,
+            hardness
+        )
+    }
+}
+
+impl HandView {
+    pub fn new(rules: &RuleSet, cards: &[u8]) -> Self {
+        let (points, soft) = rules.rate_hand(cards);
+
+        Self {
+            cards: cards.to_vec(),
+            points,
+            soft,
+        }
+    }
+}
+
+impl fmt::Display for RedactedView {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            formatter,
+            "Player: {} bytes  Dealer: {} bytes  State: ",
+            self.cards_for_player, self.cards_for_dealer
+        )?;
+
+        match self.state {
+            Some(state) => write!(formatter, "{} bytes", state),
+            None => write!(formatter, "none"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::playing_card::{PlayingCard, Suit};
+
+    #[test]
+    fn display_game() {
+        let rules = RuleSet {
+            soft_aces: true,
+            ..RuleSet::default()
+        };
+
+        let ace = PlayingCard::new(14, Suit::Spades).encode();
+        let view = GameView::new(
+            &rules,
+            &[ace, 6],
+            &[10, 13, 12],
+            NaiveGameState::DealerBusts,
+        );
+
+        assert_eq!(
+            "Player: A♠ 6♣ (17 soft)  Dealer: 10♣ K♣ Q♣ (30 hard)  DealerBusts",
+            view.to_string()
+        );
+    }
+}
@@ -0,0 +1,114 @@
+use crate::game_rules::RuleSet;
+use crate::game_transcript::GameAction;
+use crate::naive_game::NaiveGameState;
+
+use std::io::{Result, Write};
+
+use serde::{Deserialize, Serialize};
+
+const CSV_HEADER: &str = "hand_id,seed,dealer_hits_soft_17,dealer_stands_on,decks,double_down,natural_pays,soft_aces,split_pairs,cards,actions,state,payout";
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct HandRecord {
+    pub actions: Vec<GameAction>,
+    pub cards: Vec<u8>,
+    pub hand_id: u64,
+    pub payout: f64,
+    pub rules: RuleSet,
+    pub seed: u128,
+    pub state: NaiveGameState,
+}
+
+impl HandRecord {
+    fn to_csv(&self) -> String {
+        let join = |values: Vec<String>| values.join(" ");
+
+        format!(
+            "{},{},{},{},{},{},{},{},{},{},{},{:?},{}",
+            self.hand_id,
+            self.seed,
+            self.rules.dealer_hits_soft_17,
+            self.rules.dealer_stands_on,
+            self.rules
+                .decks
+                .map(|decks| decks.to_string())
+                .unwrap_or_default(),
+            self.rules.double_down,
+            self.rules.natural_pays,
+            self.rules.soft_aces,
+            self.rules.split_pairs,
+            join(self.cards.iter().map(u8::to_string).collect()),
+            join(
+                self.actions
+                    .iter()
+                    .map(|action| format!("{:?}", action))
+                    .collect()
+            ),
+            self.state,
+            self.payout
+        )
+    }
+}
+
+pub fn export_csv(records: &[HandRecord], mut writer: impl Write) -> Result<()> {
+    writeln!(writer, "{}", CSV_HEADER)?;
+
+    for record in records {
+        writeln!(writer, "{}", record.to_csv())?;
+    }
+
+    Ok(())
+}
+
+pub fn export_json(records: &[HandRecord], writer: impl Write) -> Result<()> {
+    serde_json::to_writer_pretty(writer, records)?;
+
+    Ok(())
+}
+
+pub fn payout(rules: &RuleSet, actions: &[GameAction], state: NaiveGameState) -> f64 {
+    match state {
+        NaiveGameState::PlayerWins if actions == [GameAction::CreateGame] => rules.natural_pays,
+        NaiveGameState::DealerBusts | NaiveGameState::PlayerWins => 1.0,
+        NaiveGameState::DealerWins | NaiveGameState::PlayerBusts => -1.0,
+        _ => 0.0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::naive_game::NaiveGame;
+
+    #[test]
+    fn export_hands() {
+        let mut game = NaiveGame::new(3);
+
+        game.plant_deck(&[9, 4, 8, 5, 10, 6]);
+        game.create_game();
+        game.hit_as_player();
+        game.stand();
+        game.hit_as_dealer();
+
+        let records = vec![game.hand_record(1)];
+
+        let mut csv = Vec::new();
+        let mut json = Vec::new();
+
+        export_csv(&records, &mut csv).unwrap();
+        export_json(&records, &mut json).unwrap();
+
+        assert_eq!(
+            format!(
+                "{}\n1,3,false,17,,false,1,false,false,6 10 5 8 4 9,CreateGame HitAsPlayer Stand HitAsDealer,DealerBusts,1\n",
+                CSV_HEADER
+            ),
+            String::from_utf8(csv).unwrap()
+        );
+        assert_eq!(
+            records,
+            serde_json::from_slice::<Vec<HandRecord>>(&json).unwrap()
+        );
+    }
+}
@@ -1,4 +1,23 @@
+pub mod advisor;
+pub mod allocation_tracker;
+pub mod card_counting;
+pub mod concurrency_sweep;
+pub mod game_rules;
+pub mod game_storage;
+pub mod game_transcript;
+pub mod game_view;
+pub mod hand_history;
 pub mod naive_game;
+pub mod playing_card;
+pub mod scenario;
+pub mod secure_client;
 pub mod secure_game;
+pub mod secure_hand;
+pub mod secure_profile;
+pub mod secure_protocol;
+pub mod secure_server;
+pub mod side_bets;
+pub mod simulation;
+pub mod strategy;
 pub mod tfhe_keys;
 pub mod tfhe_values;
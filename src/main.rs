@@ -1,79 +1,712 @@
 use std::env;
-use std::io::{stdout, Write};
+use std::io::{stdin, stdout, Write};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
 
+use blockjack::advisor::StrategyTable;
+use blockjack::allocation_tracker::AllocationStats;
+#[cfg(feature = "track-allocations")]
+use blockjack::allocation_tracker::TrackingAllocator;
+use blockjack::card_counting::{BetSpread, CardCounter, CountingSystem};
+use blockjack::concurrency_sweep::{self, LevelReport, SweepReport, SweepSummary};
+use blockjack::game_rules::RuleSet;
+use blockjack::game_transcript::GameAction;
+use blockjack::game_view::{GameView, HandView};
+use blockjack::hand_history::{self, HandRecord};
+use blockjack::naive_game::NaiveGameState;
+use blockjack::playing_card::{format_cards, PlayingCard, SUITS};
+use blockjack::scenario::{scenario, scenarios, Scenario, DEFAULT_SCENARIO};
+use blockjack::simulation::{
+    record_hands, simulate, simulate_counting, CountingReport, SimulationReport,
+};
+use blockjack::strategy::{BasicStrategy, MimicTheDealer, NeverBust, Strategy};
 use blockjack::tfhe_keys::initialize_keys;
 use blockjack::{naive_game::NaiveGame, secure_game::SecureGame};
 
+use rand::random_range;
 use rayon::iter::{IntoParallelIterator, ParallelIterator};
+use rayon::{ThreadPool, ThreadPoolBuilder};
 
 use tfhe::{set_server_key, ClientKey};
 
-fn blip() {
-    stdout().write(b".").unwrap();
-    stdout().flush().unwrap();
+#[cfg(feature = "track-allocations")]
+#[global_allocator]
+static ALLOCATOR: TrackingAllocator = TrackingAllocator::new();
+
+struct SweepOptions {
+    deck: Option<Vec<u8>>,
+    engine: String,
+    format: String,
+    from: usize,
+    iterations: usize,
+    scenario: String,
+    step: usize,
+    threads: usize,
+    to: usize,
+}
+
+#[derive(Clone, Copy)]
+enum PlayerTurn {
+    Doubled,
+    Split,
+    Stood,
 }
 
-fn play_naive_game() {
-    let mut game = NaiveGame::new(0);
+trait InteractiveGame: Sized {
+    fn cards_for_dealer(&self) -> Vec<u8>;
+
+    fn cards_for_player(&self) -> Vec<u8>;
 
-    let deck = vec![6, 6, 6, 6, 6, 6];
+    fn plant_deck(&mut self, deck: &[u8]);
 
-    game.plant_deck(&deck);
-    game.create_game();
-    game.hit_as_player();
-    game.stand();
-    game.hit_as_dealer();
+    fn play(&mut self, action: GameAction);
+
+    fn split(&self, deck: &[u8]) -> Self;
+
+    fn state(&self) -> NaiveGameState;
 }
 
-fn play_naive_games() {
-    for parallel_games in (10..=100).step_by(10) {
-        dbg!(parallel_games);
+impl InteractiveGame for NaiveGame {
+    fn cards_for_dealer(&self) -> Vec<u8> {
+        NaiveGame::cards_for_dealer(self).to_vec()
+    }
 
-        (0..parallel_games).into_par_iter().for_each(|_| {
-            play_naive_game();
-            blip();
-        });
+    fn cards_for_player(&self) -> Vec<u8> {
+        NaiveGame::cards_for_player(self).to_vec()
+    }
+
+    fn plant_deck(&mut self, deck: &[u8]) {
+        NaiveGame::plant_deck(self, deck);
+    }
+
+    fn play(&mut self, action: GameAction) {
+        NaiveGame::play(self, action).unwrap();
+    }
+
+    fn split(&self, deck: &[u8]) -> Self {
+        let mut game = NaiveGame::with_rules(rand::random(), self.rules());
+
+        game.plant_deck(deck);
+
+        game
+    }
+
+    fn state(&self) -> NaiveGameState {
+        NaiveGame::state(self)
+    }
+}
+
+struct InteractiveSecureGame<'info> {
+    game: SecureGame<'info>,
+    key: &'info ClientKey,
+}
+
+impl InteractiveGame for InteractiveSecureGame<'_> {
+    fn cards_for_dealer(&self) -> Vec<u8> {
+        self.game.cards_for_dealer().decrypt(self.key)
+    }
+
+    fn cards_for_player(&self) -> Vec<u8> {
+        self.game.cards_for_player().decrypt(self.key)
+    }
+
+    fn plant_deck(&mut self, deck: &[u8]) {
+        self.game.plant_deck(deck);
+    }
+
+    fn play(&mut self, action: GameAction) {
+        let start = Instant::now();
+
+        self.game.play(action).unwrap();
+
+        println!("{:?} took {:.2?}", action, start.elapsed());
+    }
+
+    fn split(&self, deck: &[u8]) -> Self {
+        let mut game = Self {
+            game: SecureGame::new(self.key),
+            key: self.key,
+        };
+
+        game.plant_deck(deck);
+
+        game
+    }
+
+    fn state(&self) -> NaiveGameState {
+        NaiveGameState::from(self.game.state())
+    }
+}
+
+fn play_dealer(game: &mut impl InteractiveGame) {
+    while game.state() == NaiveGameState::WaitingForDealer {
+        game.play(GameAction::HitAsDealer);
+    }
+}
+
+fn play_hand(game: &mut impl InteractiveGame) -> bool {
+    game.play(GameAction::CreateGame);
+
+    let turn = match play_player(game, true) {
+        Some(PlayerTurn::Split) => return play_split(game),
+        Some(turn) => turn,
+        None => return false,
+    };
+
+    play_dealer(game);
+    show_result(game, turn);
+
+    true
+}
+
+fn play_player(game: &mut impl InteractiveGame, can_split: bool) -> Option<PlayerTurn> {
+    let rules = RuleSet::default();
+
+    while game.state() == NaiveGameState::WaitingForPlayer {
+        let cards_for_player = game.cards_for_player();
+
+        println!(
+            "Player: {}  Dealer: {} ?",
+            HandView::new(&rules, &cards_for_player),
+            format_cards(&game.cards_for_dealer()[..1])
+        );
+
+        print!("hit, stand, double, split or quit? ");
+        stdout().flush().unwrap();
+
+        let mut input = String::new();
+
+        if stdin().read_line(&mut input).unwrap() == 0 {
+            return None;
+        }
+
+        let initial = cards_for_player.len() == 2;
+
+        match input.trim() {
+            "h" | "hit" => game.play(GameAction::HitAsPlayer),
+            "s" | "stand" => game.play(GameAction::Stand),
+            "d" | "double" if initial => {
+                game.play(GameAction::HitAsPlayer);
+
+                if game.state() == NaiveGameState::WaitingForPlayer {
+                    game.play(GameAction::Stand);
+                }
+
+                return Some(PlayerTurn::Doubled);
+            }
+            "d" | "double" => println!("Only the first two cards can be doubled"),
+            "p" | "split"
+                if can_split
+                    && initial
+                    && rules.rate_card(cards_for_player[0])
+                        == rules.rate_card(cards_for_player[1]) =>
+            {
+                return Some(PlayerTurn::Split)
+            }
+            "p" | "split" => println!("Only the first two cards of a pair can be split"),
+            "q" | "quit" => return None,
+            other => println!("Unknown action: '{}'", other),
+        }
+    }
+
+    Some(PlayerTurn::Stood)
+}
+
+fn play_split(game: &impl InteractiveGame) -> bool {
+    let cards_for_dealer = game.cards_for_dealer();
+
+    let mut hands = Vec::new();
+
+    for card in game.cards_for_player() {
+        let second_card: usize = random_range(0..52);
+        let second_card = PlayingCard::new((second_card % 13 + 2) as u8, SUITS[second_card / 13]);
+
+        let mut hand = game.split(&[
+            cards_for_dealer[1],
+            cards_for_dealer[0],
+            second_card.encode(),
+            card,
+        ]);
+
+        hand.play(GameAction::CreateGame);
+
+        match play_player(&mut hand, false) {
+            Some(turn) => hands.push((hand, turn)),
+            None => return false,
+        }
+    }
+
+    let mut dealer_draws: Vec<u8> = vec![];
+
+    for (hand, turn) in &mut hands {
+        hand.plant_deck(&dealer_draws);
+
+        play_dealer(hand);
+
+        if dealer_draws.is_empty() {
+            dealer_draws = hand.cards_for_dealer()[2..].iter().rev().copied().collect();
+        }
+
+        show_result(hand, *turn);
+    }
+
+    true
+}
+
+fn show_result(game: &impl InteractiveGame, turn: PlayerTurn) {
+    println!(
+        "{}",
+        GameView::new(
+            &RuleSet::default(),
+            &game.cards_for_player(),
+            &game.cards_for_dealer(),
+            game.state()
+        )
+    );
+
+    if let PlayerTurn::Doubled = turn {
+        println!("Stake doubled");
+    }
+}
+
+fn export_history(args: &[String]) {
+    let usage = || -> ! {
+        eprintln!(
+            "Usage: blockjack history [--engine naive|secure] [--format json|csv] [--hands N]"
+        );
+        std::process::exit(1);
+    };
+
+    let mut engine = "naive";
+    let mut format = "json";
+    let mut hands = 1_000;
+
+    let mut options = args.iter();
+
+    while let Some(option) = options.next() {
+        match (option.as_str(), options.next()) {
+            ("--engine", Some(value)) => engine = value,
+            ("--format", Some(value)) => format = value,
+            ("--hands", Some(value)) => hands = value.parse().unwrap_or_else(|_| usage()),
+            _ => usage(),
+        }
+    }
+
+    let records = match engine {
+        "naive" => record_hands(&BasicStrategy, RuleSet::default(), hands, 0),
+        "secure" => record_secure_hands(&BasicStrategy, hands),
+        _ => usage(),
+    };
+
+    let result = match format {
+        "csv" => hand_history::export_csv(&records, stdout()),
+        "json" => hand_history::export_json(&records, stdout()),
+        _ => usage(),
+    };
+
+    result.unwrap();
+}
+
+#[cfg(feature = "track-allocations")]
+fn measure_allocations(run: impl FnOnce()) -> Option<AllocationStats> {
+    Some(ALLOCATOR.measure(run))
+}
+
+#[cfg(not(feature = "track-allocations"))]
+fn measure_allocations(run: impl FnOnce()) -> Option<AllocationStats> {
+    run();
+
+    None
+}
+
+fn play_interactive(args: &[String]) {
+    let usage = || -> ! {
+        eprintln!("Usage: blockjack play [--engine naive|secure] [--profile]");
+        std::process::exit(1);
+    };
+
+    let mut engine = "naive";
+    let mut profile = false;
+
+    let mut options = args.iter();
+
+    while let Some(option) = options.next() {
+        match option.as_str() {
+            "--engine" => engine = options.next().unwrap_or_else(|| usage()),
+            "--profile" => profile = true,
+            _ => usage(),
+        }
+    }
+
+    let seed = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_nanos();
+
+    if profile && engine != "secure" {
+        eprintln!("Profiling is only supported by the secure engine");
+        std::process::exit(1);
+    }
+
+    if profile {
+        require_profile_feature();
+    }
+
+    match engine {
+        "naive" => {
+            for hand in 0.. {
+                if !play_hand(&mut NaiveGame::new(seed as u64 + hand)) {
+                    break;
+                }
+            }
+        }
+        "secure" => {
+            let (client_key, _) = initialize_keys();
+
+            for hand in 0.. {
+                let mut game = InteractiveSecureGame {
+                    game: SecureGame::with_seed(&client_key, seed + (hand << 64)),
+                    key: &client_key,
+                };
+
+                if profile {
+                    game.game.enable_profiling();
+                }
+
+                let playing = play_hand(&mut game);
+
+                if let Some(profile) = game.game.profile() {
+                    print!("{}", profile);
+                }
+
+                if !playing {
+                    break;
+                }
+            }
+        }
+        other => {
+            eprintln!("Unknown engine: '{}'", other);
+            std::process::exit(1);
+        }
+    }
+}
 
-        println!();
+fn play_naive_game(scenario: &Scenario) {
+    scenario.naive_game(scenario.actions.len());
+}
+
+fn play_secure_game(key: ClientKey, scenario: &Scenario) {
+    scenario.secure_game(&key, scenario.actions.len());
+}
+
+fn profile_scenarios() {
+    require_profile_feature();
+
+    let (client_key, _) = initialize_keys();
+
+    for scenario in scenarios() {
+        let mut game = SecureGame::new(&client_key);
+
+        game.enable_profiling();
+        game.plant_deck(&scenario.deck);
+
+        for &action in &scenario.actions {
+            game.play(action).unwrap();
+        }
+
+        println!("{}:\n{}", scenario.name, game.profile().unwrap());
     }
 }
 
-fn play_secure_game(key: ClientKey) {
-    let mut game = SecureGame::new(&key);
+fn record_secure_hands(strategy: &impl Strategy, hands: u64) -> Vec<HandRecord> {
+    let (client_key, _) = initialize_keys();
 
-    let deck = vec![6, 6, 6, 6, 6, 6];
+    (0..hands)
+        .map(|hand| {
+            let mut game = SecureGame::new(&client_key);
+            let rules = game.transcript().rules();
 
-    game.plant_deck(&deck);
-    game.create_game();
-    game.hit_as_player();
-    game.stand();
-    game.hit_as_dealer();
+            game.create_game();
+
+            while NaiveGameState::from(game.state()) == NaiveGameState::WaitingForPlayer {
+                let cards_for_player = game.cards_for_player().decrypt(&client_key);
+                let dealer_up_card = game.cards_for_dealer().decrypt(&client_key)[0];
+
+                match strategy.decide(&rules, &cards_for_player, dealer_up_card) {
+                    GameAction::HitAsPlayer => game.hit_as_player(),
+                    _ => game.stand(),
+                }
+            }
+
+            while NaiveGameState::from(game.state()) == NaiveGameState::WaitingForDealer {
+                game.hit_as_dealer();
+            }
+
+            game.hand_record(&client_key, hand)
+        })
+        .collect()
+}
+
+fn require_profile_feature() {
+    if !cfg!(feature = "profile") {
+        eprintln!("Profiling requires building with --features profile");
+        std::process::exit(1);
+    }
 }
 
-fn play_secure_games() {
-    let (client_key, server_key) = initialize_keys();
+fn dump_counting_report(name: &str, report: &CountingReport) {
+    println!(
+        "{}: {} hands, flat EV {:.4}, spread edge {:.4}, advantage {:.4}",
+        name,
+        report.flat.hands,
+        report.flat.expected_value(),
+        report.spread_edge(),
+        report.advantage()
+    );
+}
+
+fn dump_report(name: &str, report: &SimulationReport) {
+    let (lower, upper) = report.confidence_interval();
+
+    println!(
+        "{}: win {:.4}, loss {:.4}, push {:.4}, EV {:.4} (95% CI {:.4} to {:.4})",
+        name,
+        report.win_rate(),
+        report.loss_rate(),
+        report.push_rate(),
+        report.expected_value(),
+        lower,
+        upper
+    );
+}
+
+fn simulate_strategies() {
+    let hands = 1_000_000;
+    let rules = RuleSet::default();
+
+    dump_report(
+        "advisor",
+        &simulate(&StrategyTable::generate(&rules), rules, hands, 0),
+    );
+    dump_report("basic", &simulate(&BasicStrategy, rules, hands, 0));
+    dump_report("mimic", &simulate(&MimicTheDealer, rules, hands, 0));
+    dump_report("never-bust", &simulate(&NeverBust, rules, hands, 0));
 
-    rayon::broadcast(|_| set_server_key(server_key.clone()));
+    let shoes = 20_000;
+    let shoe_rules = RuleSet {
+        decks: Some(6),
+        ..rules
+    };
 
-    for parallel_games in (10..=100).step_by(10) {
-        dbg!(parallel_games);
+    for system in [
+        CountingSystem::HiLo,
+        CountingSystem::Ko,
+        CountingSystem::OmegaII,
+    ] {
+        let counter = CardCounter::new(system, 6);
+        let spread = BetSpread::default();
 
-        (0..parallel_games).into_par_iter().for_each(|_| {
-            play_secure_game(client_key.clone());
-            blip();
+        dump_counting_report(
+            &format!("{:?} six-deck shoe", system),
+            &simulate_counting(&BasicStrategy, shoe_rules, &counter, &spread, shoes, 0),
+        );
+        dump_counting_report(
+            &format!("{:?} infinite shoe", system),
+            &simulate_counting(&BasicStrategy, rules, &counter, &spread, shoes, 0),
+        );
+    }
+}
+
+fn sweep(
+    pool: &ThreadPool,
+    options: &SweepOptions,
+    scenario: &Scenario,
+    play_game: impl Fn() + Sync,
+) -> SweepReport {
+    let mut latencies = Vec::new();
+    let mut levels = Vec::new();
+
+    let allocations_per_game = if cfg!(feature = "track-allocations") {
+        pool.install(|| measure_allocations(&play_game))
+    } else {
+        None
+    };
+
+    let start = Instant::now();
+
+    for games in (options.from..=options.to).step_by(options.step) {
+        let mut level_latencies = Vec::new();
+
+        let level_start = Instant::now();
+
+        let allocations = measure_allocations(|| {
+            for _ in 0..options.iterations {
+                let iteration_latencies: Vec<_> = pool.install(|| {
+                    (0..games)
+                        .into_par_iter()
+                        .map(|_| {
+                            let start = Instant::now();
+
+                            play_game();
+
+                            start.elapsed()
+                        })
+                        .collect()
+                });
+
+                level_latencies.extend(iteration_latencies);
+            }
         });
 
-        println!();
+        levels.push(LevelReport::new(
+            games,
+            level_start.elapsed(),
+            &level_latencies,
+            allocations,
+        ));
+        latencies.extend(level_latencies);
+    }
+
+    SweepReport {
+        levels,
+        summary: SweepSummary {
+            allocations_per_game,
+            ..SweepSummary::new(
+                &options.engine,
+                &scenario.name,
+                &scenario.deck,
+                options.iterations,
+                pool.current_num_threads(),
+                start.elapsed(),
+                &latencies,
+            )
+        },
     }
 }
 
+fn sweep_games(options: SweepOptions) {
+    let mut scenario = scenario(&options.scenario).unwrap_or_else(|| {
+        eprintln!("Unknown scenario: '{}'", options.scenario);
+        std::process::exit(1);
+    });
+
+    if let Some(deck) = &options.deck {
+        scenario.deck = deck.clone();
+    }
+
+    let pool = ThreadPoolBuilder::new()
+        .num_threads(options.threads)
+        .build()
+        .unwrap();
+
+    let report = match options.engine.as_str() {
+        "naive" => sweep(&pool, &options, &scenario, || play_naive_game(&scenario)),
+        "secure" => {
+            let (client_key, server_key) = initialize_keys();
+
+            pool.broadcast(|_| set_server_key(server_key.clone()));
+
+            sweep(&pool, &options, &scenario, || {
+                play_secure_game(client_key.clone(), &scenario)
+            })
+        }
+        other => {
+            eprintln!("Unknown engine: '{}'", other);
+            std::process::exit(1);
+        }
+    };
+
+    let result = match options.format.as_str() {
+        "csv" => concurrency_sweep::export_csv(&report, stdout()),
+        "json" => concurrency_sweep::export_json(&report, stdout()),
+        other => {
+            eprintln!("Unknown format: '{}'", other);
+            std::process::exit(1);
+        }
+    };
+
+    result.unwrap();
+}
+
+fn sweep_options(engine: &str, args: &[String]) -> SweepOptions {
+    let usage = || -> ! {
+        eprintln!(
+            "Usage: blockjack sweep [--engine naive|secure] [--from N] [--to N] [--step N] \
+             [--iterations N] [--threads N] [--scenario NAME] [--deck CARDS] [--format json|csv]"
+        );
+        std::process::exit(1);
+    };
+
+    let parse = |value: &str| value.parse().unwrap_or_else(|_| usage());
+
+    let mut options = SweepOptions {
+        deck: None,
+        engine: engine.to_string(),
+        format: "json".to_string(),
+        from: 10,
+        iterations: 1,
+        scenario: DEFAULT_SCENARIO.to_string(),
+        step: 10,
+        threads: 0,
+        to: 100,
+    };
+
+    let mut values = args.iter();
+
+    while let Some(option) = values.next() {
+        match (option.as_str(), values.next()) {
+            ("--deck", Some(value)) => {
+                let cards = value
+                    .split(',')
+                    .map(|card| card.parse().unwrap_or_else(|_| usage()));
+
+                options.deck = Some(cards.collect());
+            }
+            ("--engine", Some(value)) => options.engine = value.clone(),
+            ("--format", Some(value)) => options.format = value.clone(),
+            ("--from", Some(value)) => options.from = parse(value),
+            ("--iterations", Some(value)) => options.iterations = parse(value),
+            ("--scenario", Some(value)) => options.scenario = value.clone(),
+            ("--step", Some(value)) => options.step = parse(value),
+            ("--threads", Some(value)) => options.threads = parse(value),
+            ("--to", Some(value)) => options.to = parse(value),
+            _ => usage(),
+        }
+    }
+
+    if options.step == 0 {
+        usage();
+    }
+
+    options
+}
+
 fn main() {
     let args: Vec<String> = env::args().skip(1).collect();
 
+    if args.first().map(String::as_str) == Some("history") {
+        export_history(&args[1..]);
+
+        return;
+    }
+
+    if args.first().map(String::as_str) == Some("play") {
+        play_interactive(&args[1..]);
+
+        return;
+    }
+
+    if args.first().map(String::as_str) == Some("sweep") {
+        sweep_games(sweep_options("naive", &args[1..]));
+
+        return;
+    }
+
     for arg in args {
         match arg.as_str() {
-            "naive" => play_naive_games(),
-            "secure" => play_secure_games(),
+            "--profile" => profile_scenarios(),
+            "naive" => sweep_games(sweep_options("naive", &[])),
+            "secure" => sweep_games(sweep_options("secure", &[])),
+            "simulate" => simulate_strategies(),
             other => {
                 eprintln!("Unknown command: '{}'", other);
                 std::process::exit(1);
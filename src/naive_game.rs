@@ -1,22 +1,56 @@
-use rand_chacha::rand_core::{RngCore, SeedableRng};
+use crate::game_rules::RuleSet;
+use crate::game_storage::{load_snapshot, save_snapshot, StorageError};
+use crate::game_transcript::{ActionError, GameAction};
+use crate::game_view::GameView;
+use crate::hand_history::{payout, HandRecord};
+use crate::playing_card::{PlayingCard, SUITS};
+use crate::side_bets::{SideBetPayouts, SideBets};
+
+use std::fmt;
+
+use rand::{Rng, SeedableRng};
 use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
 
+#[derive(Clone, Deserialize, Serialize)]
 pub struct NaiveGame {
+    actions: Vec<GameAction>,
     cards_for_dealer: Vec<u8>,
     cards_for_player: Vec<u8>,
+    dealt: Vec<u8>,
     deck: Vec<u8>,
     rng: ChaCha8Rng,
+    rules: RuleSet,
+    seed: u64,
     state: NaiveGameState,
 }
 
+impl fmt::Display for NaiveGame {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        self.view().fmt(formatter)
+    }
+}
+
 impl NaiveGame {
+    pub fn actions(&self) -> &[GameAction] {
+        &self.actions
+    }
+
+    pub fn cards_for_dealer(&self) -> &[u8] {
+        &self.cards_for_dealer
+    }
+
+    pub fn cards_for_player(&self) -> &[u8] {
+        &self.cards_for_player
+    }
+
     fn check_dealer(&mut self) {
         self.state = NaiveGameState::Checking;
 
-        let points_for_player = self.rate_cards(&self.cards_for_player);
-        let points_for_dealer = self.rate_cards(&self.cards_for_dealer);
+        let points_for_player = self.rules.rate_cards(&self.cards_for_player);
+        let (points_for_dealer, soft) = self.rules.rate_hand(&self.cards_for_dealer);
 
-        self.state = if points_for_dealer < 17 {
+        self.state = if self.rules.dealer_must_hit(points_for_dealer, soft) {
             NaiveGameState::WaitingForDealer
         } else {
             if points_for_dealer > 21 {
@@ -30,8 +64,8 @@ impl NaiveGame {
     fn check_dealer_and_player(&mut self) {
         self.state = NaiveGameState::Checking;
 
-        let points_for_player = self.rate_cards(&self.cards_for_player);
-        let points_for_dealer = self.rate_cards(&self.cards_for_dealer);
+        let points_for_player = self.rules.rate_cards(&self.cards_for_player);
+        let points_for_dealer = self.rules.rate_cards(&self.cards_for_dealer);
 
         self.state = if points_for_player == 21 {
             NaiveGameState::PlayerWins
@@ -55,7 +89,7 @@ impl NaiveGame {
     fn check_player(&mut self) {
         self.state = NaiveGameState::Checking;
 
-        let points_for_player = self.rate_cards(&self.cards_for_player);
+        let points_for_player = self.rules.rate_cards(&self.cards_for_player);
 
         self.state = if points_for_player > 21 {
             NaiveGameState::PlayerBusts
@@ -68,15 +102,24 @@ impl NaiveGame {
         self.deal_player(2);
         self.deal_dealer(2);
         self.check_dealer_and_player();
+        self.actions.push(GameAction::CreateGame);
+    }
+
+    fn deal_card(&mut self) -> u8 {
+        let card = if self.deck.is_empty() {
+            self.random_card()
+        } else {
+            self.deck.pop().unwrap()
+        };
+
+        self.dealt.push(card);
+
+        card
     }
 
     fn deal_dealer(&mut self, count: u8) {
         for _ in 0..count {
-            let card = if self.deck.is_empty() {
-                self.random_card()
-            } else {
-                self.deck.pop().unwrap()
-            };
+            let card = self.deal_card();
 
             self.cards_for_dealer.push(card);
         }
@@ -84,18 +127,27 @@ impl NaiveGame {
 
     fn deal_player(&mut self, count: u8) {
         for _ in 0..count {
-            let card = if self.deck.is_empty() {
-                self.random_card()
-            } else {
-                self.deck.pop().unwrap()
-            };
+            let card = self.deal_card();
+
+            self.cards_for_player.push(card);
+        }
+    }
+
+    pub fn decide(&mut self, hit: bool) {
+        let card = self.deal_card();
 
+        if hit {
             self.cards_for_player.push(card);
+            self.check_player();
+        } else {
+            self.check_dealer();
         }
+
+        self.actions.push(GameAction::Decide);
     }
 
     pub fn dump_game(&self) {
-        dbg!(&self.cards_for_player, &self.cards_for_dealer, &self.state);
+        println!("{}", self);
     }
 
     fn game_over(&self, points_for_dealer: u8, points_for_player: u8) -> NaiveGameState {
@@ -110,58 +162,144 @@ impl NaiveGame {
         }
     }
 
+    pub fn hand_record(&self, hand_id: u64) -> HandRecord {
+        HandRecord {
+            actions: self.actions.clone(),
+            cards: self.dealt.clone(),
+            hand_id,
+            payout: payout(&self.rules, &self.actions, self.state),
+            rules: self.rules,
+            seed: self.seed as u128,
+            state: self.state,
+        }
+    }
+
     pub fn hit_as_dealer(&mut self) {
         self.deal_dealer(1);
         self.check_dealer();
+        self.actions.push(GameAction::HitAsDealer);
     }
 
     pub fn hit_as_player(&mut self) {
         self.deal_player(1);
         self.check_player();
+        self.actions.push(GameAction::HitAsPlayer);
+    }
+
+    pub fn load(buffer: &[u8]) -> Result<Self, StorageError> {
+        load_snapshot(buffer)
     }
 
     pub fn new(seed: u64) -> Self {
         Self {
+            actions: vec![],
             cards_for_dealer: vec![],
             cards_for_player: vec![],
+            dealt: vec![],
             deck: vec![],
             rng: ChaCha8Rng::seed_from_u64(seed),
+            rules: RuleSet::default(),
+            seed,
             state: NaiveGameState::Uninitialized,
         }
     }
 
+    pub fn next_hand(&mut self) {
+        self.actions.clear();
+        self.cards_for_dealer.clear();
+        self.dealt.clear();
+        self.cards_for_player.clear();
+        self.state = NaiveGameState::Uninitialized;
+    }
+
     pub fn plant_deck(&mut self, deck: &[u8]) {
         self.deck.extend(deck);
     }
 
-    fn random_card(&mut self) -> u8 {
-        (self.rng.next_u32() % 13 + 2) as u8
+    pub fn play(&mut self, action: GameAction) -> Result<(), ActionError> {
+        match action {
+            GameAction::CreateGame => self.create_game(),
+            GameAction::Decide => return Err(ActionError::DecisionRequired),
+            GameAction::HitAsDealer => self.hit_as_dealer(),
+            GameAction::HitAsPlayer => self.hit_as_player(),
+            GameAction::Stand => self.stand(),
+        }
+
+        Ok(())
     }
 
-    fn rate_card(&self, card: u8) -> u8 {
-        if card < 11 {
-            card
-        } else {
-            if card < 14 {
-                10
-            } else {
-                11
+    pub fn play_out_dealer(&mut self, draws: usize) {
+        for _ in 0..draws {
+            let card = self.deal_card();
+
+            if self.state == NaiveGameState::WaitingForDealer {
+                self.cards_for_dealer.push(card);
+                self.check_dealer();
             }
         }
     }
 
-    fn rate_cards(&self, cards: &[u8]) -> u8 {
-        cards
-            .iter()
-            .fold(0, |total, &card| total + self.rate_card(card))
+    fn random_card(&mut self) -> u8 {
+        let card: usize = self.rng.random_range(0..52);
+
+        PlayingCard::new((card % 13 + 2) as u8, SUITS[card / 13]).encode()
+    }
+
+    pub fn rules(&self) -> RuleSet {
+        self.rules
+    }
+
+    pub fn save(&self) -> Vec<u8> {
+        save_snapshot(self)
+    }
+
+    pub fn side_bets(&self, payouts: &SideBetPayouts) -> Option<SideBets> {
+        if self.cards_for_player.len() < 2 {
+            return None;
+        }
+
+        let dealer_up_card = *self.cards_for_dealer.first()?;
+
+        Some(SideBets::evaluate(
+            &self.cards_for_player,
+            dealer_up_card,
+            payouts,
+        ))
     }
 
     pub fn stand(&mut self) {
         self.check_dealer();
+        self.actions.push(GameAction::Stand);
+    }
+
+    pub fn state(&self) -> NaiveGameState {
+        self.state
+    }
+
+    pub fn view(&self) -> GameView {
+        GameView::new(
+            &self.rules,
+            &self.cards_for_player,
+            &self.cards_for_dealer,
+            self.state,
+        )
+    }
+
+    pub fn with_rules(seed: u64, rules: RuleSet) -> Self {
+        let mut game = Self {
+            rules,
+            ..Self::new(seed)
+        };
+
+        if let Some(shoe) = rules.shuffle_shoe(&mut game.rng) {
+            game.deck = shoe;
+        }
+
+        game
     }
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
 pub enum NaiveGameState {
     Uninitialized,
     Checking,
@@ -178,6 +316,9 @@ pub enum NaiveGameState {
 mod tests {
     use super::*;
 
+    use crate::playing_card::Suit;
+    use crate::side_bets::{PerfectPairs, TwentyOnePlusThree};
+
     const J: u8 = 11;
     const Q: u8 = 12;
     const K: u8 = 13;
@@ -305,6 +446,39 @@ mod tests {
         assert_eq!(NaiveGameState::Tie, game.state);
     }
 
+    #[test]
+    fn hidden_decisions() {
+        let mut game = NaiveGame::new(0);
+
+        let deck = vec![5, 2, 9, 8, 7, 6];
+
+        game.plant_deck(&deck);
+        game.create_game();
+        game.decide(true);
+
+        assert_eq!(NaiveGameState::WaitingForPlayer, game.state);
+
+        game.decide(false);
+
+        assert_eq!(vec!(6, 7, 2), game.cards_for_player);
+        assert_eq!(vec!(8, 9), game.cards_for_dealer);
+        assert_eq!(NaiveGameState::DealerWins, game.state);
+    }
+
+    #[test]
+    fn play_decisions_with_decide() {
+        let mut game = NaiveGame::new(0);
+
+        game.plant_deck(&[9, 8, 7, 6]);
+        game.create_game();
+
+        assert_eq!(
+            Err(ActionError::DecisionRequired),
+            game.play(GameAction::Decide)
+        );
+        assert_eq!(NaiveGameState::WaitingForPlayer, game.state);
+    }
+
     #[test]
     fn player_busts_early() {
         let mut game = NaiveGame::new(0);
@@ -390,4 +564,71 @@ mod tests {
         assert_eq!(vec!(J, Q), game.cards_for_dealer);
         assert_eq!(NaiveGameState::PlayerWins, game.state);
     }
+
+    #[test]
+    fn rate_side_bets() {
+        let mut game = NaiveGame::new(0);
+
+        let deck = [
+            (10, Suit::Clubs),
+            (7, Suit::Diamonds),
+            (7, Suit::Hearts),
+            (7, Suit::Hearts),
+        ]
+        .map(|(rank, suit)| PlayingCard::new(rank, suit).encode());
+
+        game.plant_deck(&deck);
+
+        let payouts = SideBetPayouts::default();
+
+        assert_eq!(None, game.side_bets(&payouts));
+
+        game.create_game();
+
+        assert_eq!(
+            Some(SideBets {
+                perfect_pairs: PerfectPairs::PerfectPair,
+                perfect_pairs_payout: 25,
+                twenty_one_plus_three: TwentyOnePlusThree::ThreeOfAKind,
+                twenty_one_plus_three_payout: 30,
+            }),
+            game.side_bets(&payouts)
+        );
+    }
+
+    #[test]
+    fn resume_game() {
+        let mut game = NaiveGame::new(0);
+
+        let deck = vec![3, 2];
+
+        game.plant_deck(&deck);
+        game.create_game();
+
+        let mut resumed_game = NaiveGame::load(&game.save()).unwrap();
+
+        game.hit_as_player();
+        game.stand();
+
+        resumed_game.hit_as_player();
+        resumed_game.stand();
+
+        assert_eq!(game.cards_for_player, resumed_game.cards_for_player);
+        assert_eq!(game.cards_for_dealer, resumed_game.cards_for_dealer);
+        assert_eq!(game.state, resumed_game.state);
+    }
+
+    #[test]
+    fn resume_game_with_unsupported_version() {
+        let game = NaiveGame::new(0);
+
+        let mut buffer = game.save();
+
+        buffer[0] += 1;
+
+        assert_eq!(
+            Some(StorageError::UnsupportedVersion(buffer[0])),
+            NaiveGame::load(&buffer).err()
+        );
+    }
 }
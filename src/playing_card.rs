@@ -0,0 +1,94 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+pub const RANK_MASK: u8 = 0x0f;
+pub const SUIT_SHIFT: u8 = 4;
+pub const SUITS: [Suit; 4] = [Suit::Clubs, Suit::Diamonds, Suit::Hearts, Suit::Spades];
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub struct PlayingCard {
+    pub rank: u8,
+    pub suit: Suit,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub enum Suit {
+    Clubs,
+    Diamonds,
+    Hearts,
+    Spades,
+}
+
+impl fmt::Display for PlayingCard {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "{}{}", rank_symbol(self.rank), self.suit)
+    }
+}
+
+impl PlayingCard {
+    pub fn decode(card: u8) -> Self {
+        Self {
+            rank: rank(card),
+            suit: SUITS[(card >> SUIT_SHIFT) as usize % SUITS.len()],
+        }
+    }
+
+    pub fn encode(&self) -> u8 {
+        (self.suit as u8) << SUIT_SHIFT | self.rank
+    }
+
+    pub fn new(rank: u8, suit: Suit) -> Self {
+        Self { rank, suit }
+    }
+}
+
+impl fmt::Display for Suit {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        let symbol = match self {
+            Suit::Clubs => "♣",
+            Suit::Diamonds => "♦",
+            Suit::Hearts => "♥",
+            Suit::Spades => "♠",
+        };
+
+        formatter.write_str(symbol)
+    }
+}
+
+pub fn format_cards(cards: &[u8]) -> String {
+    cards
+        .iter()
+        .map(|&card| PlayingCard::decode(card).to_string())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+pub fn rank(card: u8) -> u8 {
+    card & RANK_MASK
+}
+
+pub fn rank_symbol(rank: u8) -> String {
+    match rank {
+        11 => "J".to_string(),
+        12 => "Q".to_string(),
+        13 => "K".to_string(),
+        14 => "A".to_string(),
+        rank => rank.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encode_cards() {
+        let card = PlayingCard::new(14, Suit::Spades);
+
+        assert_eq!(0x3e, card.encode());
+        assert_eq!(card, PlayingCard::decode(card.encode()));
+        assert_eq!(PlayingCard::new(9, Suit::Clubs), PlayingCard::decode(9));
+        assert_eq!("A♠ 10♥ 9♣", format_cards(&[0x3e, 0x2a, 9]));
+    }
+}
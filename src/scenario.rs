@@ -0,0 +1,171 @@
+use crate::game_transcript::GameAction;
+use crate::naive_game::{NaiveGame, NaiveGameState};
+use crate::secure_game::SecureGame;
+
+use std::fs;
+use std::io::{Error, ErrorKind, Result};
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use tfhe::ClientKey;
+
+pub const DEFAULT_SCENARIO: &str = "tie";
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Scenario {
+    pub actions: Vec<GameAction>,
+    pub deck: Vec<u8>,
+    pub name: String,
+    pub outcome: NaiveGameState,
+}
+
+impl Scenario {
+    pub fn load(path: impl AsRef<Path>) -> Result<Vec<Self>> {
+        let scenarios: Vec<Self> = serde_json::from_slice(&fs::read(path)?)?;
+
+        if scenarios
+            .iter()
+            .any(|scenario| scenario.actions.contains(&GameAction::Decide))
+        {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                "Scenarios cannot contain decisions",
+            ));
+        }
+
+        Ok(scenarios)
+    }
+
+    pub fn naive_game(&self, steps: usize) -> NaiveGame {
+        let mut game = NaiveGame::new(0);
+
+        game.plant_deck(&self.deck);
+
+        for &action in &self.actions[..steps] {
+            game.play(action).unwrap();
+        }
+
+        game
+    }
+
+    fn new(name: &str, deck: &[u8], actions: &[GameAction], outcome: NaiveGameState) -> Self {
+        Self {
+            actions: actions.to_vec(),
+            deck: deck.to_vec(),
+            name: name.to_string(),
+            outcome,
+        }
+    }
+
+    pub fn secure_game<'info>(&self, key: &'info ClientKey, steps: usize) -> SecureGame<'info> {
+        let mut game = SecureGame::new(key);
+
+        game.plant_deck(&self.deck);
+
+        for &action in &self.actions[..steps] {
+            game.play(action).unwrap();
+        }
+
+        game
+    }
+}
+
+pub fn scenario(name: &str) -> Option<Scenario> {
+    scenarios()
+        .into_iter()
+        .find(|scenario| scenario.name == name)
+}
+
+pub fn scenarios() -> Vec<Scenario> {
+    vec![
+        Scenario::new(
+            "dealer-busts",
+            &[10, 6, 10, 8, 10],
+            &[
+                GameAction::CreateGame,
+                GameAction::Stand,
+                GameAction::HitAsDealer,
+            ],
+            NaiveGameState::DealerBusts,
+        ),
+        Scenario::new(
+            "early-bust",
+            &[10, 8, 9, 6, 10],
+            &[GameAction::CreateGame, GameAction::HitAsPlayer],
+            NaiveGameState::PlayerBusts,
+        ),
+        Scenario::new(
+            "long-hand",
+            &[5, 4, 2, 3, 2, 4, 3, 2, 2, 2, 3, 2],
+            &[
+                GameAction::CreateGame,
+                GameAction::HitAsPlayer,
+                GameAction::HitAsPlayer,
+                GameAction::HitAsPlayer,
+                GameAction::HitAsPlayer,
+                GameAction::Stand,
+                GameAction::HitAsDealer,
+                GameAction::HitAsDealer,
+                GameAction::HitAsDealer,
+                GameAction::HitAsDealer,
+            ],
+            NaiveGameState::DealerWins,
+        ),
+        Scenario::new(
+            "natural",
+            &[8, 9, 13, 14],
+            &[GameAction::CreateGame],
+            NaiveGameState::PlayerWins,
+        ),
+        Scenario::new(
+            DEFAULT_SCENARIO,
+            &[6, 6, 6, 6, 6, 6],
+            &[
+                GameAction::CreateGame,
+                GameAction::HitAsPlayer,
+                GameAction::Stand,
+                GameAction::HitAsDealer,
+            ],
+            NaiveGameState::Tie,
+        ),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::tfhe_keys::initialize_keys;
+
+    use std::{env, process};
+
+    #[test]
+    fn play_scenarios() {
+        for scenario in scenarios() {
+            let game = scenario.naive_game(scenario.actions.len());
+
+            assert_eq!(scenario.outcome, game.state(), "{}", scenario.name);
+        }
+
+        let path = env::temp_dir().join(format!("blockjack-scenarios-{}.json", process::id()));
+
+        fs::write(&path, serde_json::to_vec(&scenarios()).unwrap()).unwrap();
+
+        let loaded = Scenario::load(&path);
+
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(scenarios(), loaded.unwrap());
+    }
+
+    #[test]
+    fn play_secure_scenarios() {
+        let (client_key, _) = initialize_keys();
+
+        for scenario in scenarios() {
+            let game = scenario.secure_game(&client_key, scenario.actions.len());
+
+            assert_eq!(scenario.outcome, game.state().into(), "{}", scenario.name);
+        }
+    }
+}
@@ -0,0 +1,113 @@
+use crate::game_transcript::GameAction;
+use crate::secure_game::SecureGameState;
+use crate::secure_hand::SecureHand;
+use crate::secure_protocol::{
+    read_message, write_message, ClientMessage, EncryptedResult, ServerMessage,
+};
+use crate::tfhe_values::{deserialize, encrypt_decision, serialize, FheState};
+
+use std::io::{Cursor, Error, ErrorKind, Result};
+use std::net::TcpStream;
+
+use tfhe::prelude::FheDecrypt;
+use tfhe::safe_serialization::safe_serialize;
+use tfhe::{ClientKey, ServerKey};
+
+pub struct SecureClient<'info> {
+    key: &'info ClientKey,
+    stream: TcpStream,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct HandResult {
+    pub cards_for_dealer: Vec<u8>,
+    pub cards_for_player: Vec<u8>,
+    pub commitment: [u8; 32],
+    pub state: SecureGameState,
+}
+
+impl<'info> SecureClient<'info> {
+    pub fn connect(key: &'info ClientKey, address: &str) -> Result<Self> {
+        Ok(Self {
+            key,
+            stream: TcpStream::connect(address)?,
+        })
+    }
+
+    pub fn decide(&mut self, hit: bool) -> Result<HandResult> {
+        let decision = serialize(&encrypt_decision(self.key, hit));
+
+        self.request(ClientMessage::Decide(decision))
+    }
+
+    fn decrypt_result(&self, result: EncryptedResult) -> Result<HandResult> {
+        let corrupt = |_| Error::new(ErrorKind::InvalidData, "Corrupt result");
+
+        let state_value: u8 = deserialize::<FheState>(&result.state)
+            .map_err(corrupt)?
+            .decrypt(self.key);
+
+        Ok(HandResult {
+            cards_for_dealer: SecureHand::load(&result.cards_for_dealer)
+                .map_err(corrupt)?
+                .decrypt(self.key),
+            cards_for_player: SecureHand::load(&result.cards_for_player)
+                .map_err(corrupt)?
+                .decrypt(self.key),
+            commitment: result.commitment,
+            state: SecureGameState::try_from(state_value)
+                .map_err(|_| Error::new(ErrorKind::InvalidData, "Corrupt state"))?,
+        })
+    }
+
+    pub fn new_hand(&mut self) -> Result<HandResult> {
+        self.request(ClientMessage::NewHand)
+    }
+
+    pub fn play(&mut self, action: GameAction) -> Result<HandResult> {
+        self.request(ClientMessage::Action(action))
+    }
+
+    pub fn register_key(&mut self, server_key: &ServerKey) -> Result<()> {
+        let mut buffer = Vec::new();
+
+        safe_serialize(server_key, &mut Cursor::new(&mut buffer), u64::MAX)
+            .map_err(|error| Error::new(ErrorKind::InvalidData, error.to_string()))?;
+
+        write_message(&mut self.stream, &ClientMessage::RegisterKey(buffer))?;
+
+        match read_message(&mut self.stream)? {
+            ServerMessage::KeyRegistered => Ok(()),
+            ServerMessage::Error(message) => Err(Error::other(message)),
+            ServerMessage::EncryptedResult(_) => Err(Error::other("Unexpected result")),
+            ServerMessage::StateRevealed => Err(Error::other("Unexpected acknowledgement")),
+        }
+    }
+
+    fn request(&mut self, message: ClientMessage) -> Result<HandResult> {
+        write_message(&mut self.stream, &message)?;
+
+        let result = match read_message(&mut self.stream)? {
+            ServerMessage::EncryptedResult(result) => self.decrypt_result(result)?,
+            ServerMessage::Error(message) => return Err(Error::other(message)),
+            ServerMessage::KeyRegistered | ServerMessage::StateRevealed => {
+                return Err(Error::other("Unexpected acknowledgement"))
+            }
+        };
+
+        self.reveal_state(result.state)?;
+
+        Ok(result)
+    }
+
+    fn reveal_state(&mut self, state: SecureGameState) -> Result<()> {
+        write_message(&mut self.stream, &ClientMessage::RevealState(state as u8))?;
+
+        match read_message(&mut self.stream)? {
+            ServerMessage::StateRevealed => Ok(()),
+            ServerMessage::Error(message) => Err(Error::other(message)),
+            ServerMessage::EncryptedResult(_) => Err(Error::other("Unexpected result")),
+            ServerMessage::KeyRegistered => Err(Error::other("Unexpected acknowledgement")),
+        }
+    }
+}
@@ -1,34 +1,98 @@
-use crate::tfhe_values::{decrypt_cards, encrypt_points, encrypt_state};
+use crate::game_rules::RuleSet;
+use crate::game_storage::{load_snapshot, save_snapshot, StorageError};
+use crate::game_transcript::{ActionError, DealtCard, GameAction, GameTranscript};
+use crate::game_view::{GameView, RedactedView};
+use crate::hand_history::{payout, HandRecord};
+use crate::naive_game::NaiveGameState;
+use crate::secure_hand::{HandSnapshot, SecureHand};
+use crate::secure_profile::{and, decrypt, eq, gt, lt, select, HandProfile, Profiler};
+use crate::side_bets::{
+    rate_encrypted_perfect_pairs, rate_encrypted_twenty_one_plus_three, EncryptedSideBets,
+};
+use crate::tfhe_keys::join_with_server_key;
+use crate::tfhe_values::{
+    deserialize, encrypt_card, generate_card, serialize, state_size, FheCard, FhePoints, FheState,
+    TrivialStates,
+};
+
+use std::fmt;
+
+use rand::rngs::OsRng;
+use rand::{Rng, TryRngCore};
+use serde::{Deserialize, Serialize};
+use tfhe::prelude::{FheDecrypt, FheTrivialEncrypt};
+use tfhe::{ClientKey, FheBool, ServerKey};
+
+pub const DEALER_DRAWS: usize = 7;
+
+pub struct EncryptedState(FheState);
 
-use std::sync::atomic::{AtomicUsize, Ordering};
+pub struct SecureGame<'info> {
+    cards_for_dealer: SecureHand,
+    cards_for_player: SecureHand,
+    constants: TrivialStates,
+    deck: Vec<FheCard>,
+    encrypted_state: Option<EncryptedState>,
+    key: Option<&'info ClientKey>,
+    profiler: Option<Profiler>,
+    reveal: RevealPolicy,
+    seed: u128,
+    server_key: Option<ServerKey>,
+    state: SecureGameState,
+    transcript: GameTranscript,
+}
+
+#[derive(Deserialize, Serialize)]
+struct SecureGameSnapshot {
+    cards_for_dealer: HandSnapshot,
+    cards_for_player: HandSnapshot,
+    deck: Vec<Vec<u8>>,
+    encrypted_state: Option<Vec<u8>>,
+    reveal: RevealPolicy,
+    seed: u128,
+    state: u8,
+    transcript: GameTranscript,
+}
 
-use tfhe::prelude::{DivRem, FheDecrypt, FheEq, FheOrd, IfThenElse};
-use tfhe::{ClientKey, FheUint8, Seed};
+impl EncryptedState {
+    pub fn ciphertext(&self) -> &FheState {
+        &self.0
+    }
 
-pub static SEED_COUNTER: AtomicUsize = AtomicUsize::new(0);
+    pub fn decrypt(&self, key: &ClientKey) -> SecureGameState {
+        let state_value: u8 = decrypt(&self.0, key);
 
-pub struct SecureGame<'info> {
-    cards_for_dealer: Vec<FheUint8>,
-    cards_for_player: Vec<FheUint8>,
-    deck: Vec<FheUint8>,
-    key: &'info ClientKey,
-    state: SecureGameState,
+        SecureGameState::try_from(state_value).unwrap()
+    }
+
+    pub fn is_over(&self) -> FheBool {
+        lt(&self.0, SecureGameState::WaitingForDealer as u8)
+    }
+}
+
+impl fmt::Debug for SecureGame<'_> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter
+            .debug_struct("SecureGame")
+            .field("redacted", &self.redacted_view())
+            .field("state", &self.state)
+            .finish()
+    }
 }
 
 impl<'info> SecureGame<'info> {
+    pub fn cards_for_dealer(&self) -> &SecureHand {
+        &self.cards_for_dealer
+    }
+
+    pub fn cards_for_player(&self) -> &SecureHand {
+        &self.cards_for_player
+    }
+
     fn check_dealer(&mut self) {
         self.state = SecureGameState::Checking;
 
-        let points_for_player = self.rate_cards(&self.cards_for_player);
-        let points_for_dealer = self.rate_cards(&self.cards_for_dealer);
-
-        let state = points_for_dealer.lt(17).select(
-            &encrypt_state(self.key, SecureGameState::WaitingForDealer),
-            &points_for_dealer.gt(21).select(
-                &encrypt_state(self.key, SecureGameState::DealerBusts),
-                &self.game_over(&points_for_dealer, &points_for_player),
-            ),
-        );
+        let state = self.dealer_state();
 
         self.decrypt_state(state);
     }
@@ -36,18 +100,27 @@ impl<'info> SecureGame<'info> {
     fn check_dealer_and_player(&mut self) {
         self.state = SecureGameState::Checking;
 
-        let points_for_player = self.rate_cards(&self.cards_for_player);
-        let points_for_dealer = self.rate_cards(&self.cards_for_dealer);
-
-        let state = points_for_player.eq(21).select(
-            &encrypt_state(self.key, SecureGameState::PlayerWins),
-            &points_for_player.gt(21).select(
-                &encrypt_state(self.key, SecureGameState::PlayerBusts),
-                &points_for_dealer.eq(21).select(
-                    &encrypt_state(self.key, SecureGameState::DealerWins),
-                    &points_for_dealer.gt(21).select(
-                        &encrypt_state(self.key, SecureGameState::DealerBusts),
-                        &encrypt_state(self.key, SecureGameState::WaitingForPlayer),
+        let points_for_player = self.cards_for_player.rate_cards();
+        let points_for_dealer = self.cards_for_dealer.rate_cards();
+
+        let ((player_has_21, player_busts), (dealer_has_21, dealer_busts)) = self.join(
+            || self.join(|| eq(&points_for_player, 21), || gt(&points_for_player, 21)),
+            || self.join(|| eq(&points_for_dealer, 21), || gt(&points_for_dealer, 21)),
+        );
+
+        let state = select(
+            &player_has_21,
+            self.constants.get(SecureGameState::PlayerWins),
+            &select(
+                &player_busts,
+                self.constants.get(SecureGameState::PlayerBusts),
+                &select(
+                    &dealer_has_21,
+                    self.constants.get(SecureGameState::DealerWins),
+                    &select(
+                        &dealer_busts,
+                        self.constants.get(SecureGameState::DealerBusts),
+                        self.constants.get(SecureGameState::WaitingForPlayer),
                     ),
                 ),
             ),
@@ -59,128 +132,437 @@ impl<'info> SecureGame<'info> {
     fn check_player(&mut self) {
         self.state = SecureGameState::Checking;
 
-        let points_for_player = self.rate_cards(&self.cards_for_player);
-
-        let state = points_for_player.gt(21).select(
-            &encrypt_state(self.key, SecureGameState::PlayerBusts),
-            &encrypt_state(self.key, SecureGameState::WaitingForPlayer),
-        );
+        let state = self.player_state();
 
         self.decrypt_state(state);
     }
 
     pub fn create_game(&mut self) {
+        self.start_action();
         self.deal_player(2);
         self.deal_dealer(2);
         self.check_dealer_and_player();
+        self.record_action(GameAction::CreateGame);
+    }
+
+    fn deal_card(&mut self) -> FheCard {
+        let (card, seed) = if self.deck.is_empty() {
+            let seed = self.seed;
+
+            self.seed = self.seed.wrapping_add(1);
+
+            (generate_card(seed), Some(seed))
+        } else {
+            (self.deck.pop().unwrap(), None)
+        };
+
+        self.transcript.record_card(&card, seed);
+
+        card
     }
 
     fn deal_dealer(&mut self, count: u8) {
         for _ in 0..count {
-            let card = if self.deck.is_empty() {
-                self.random_card()
-            } else {
-                self.deck.pop().unwrap()
-            };
+            let card = self.deal_card();
 
-            self.cards_for_dealer.push(card);
+            self.cards_for_dealer.push(&card);
         }
     }
 
     fn deal_player(&mut self, count: u8) {
         for _ in 0..count {
-            let card = if self.deck.is_empty() {
-                self.random_card()
-            } else {
-                self.deck.pop().unwrap()
-            };
+            let card = self.deal_card();
 
-            self.cards_for_player.push(card);
+            self.cards_for_player.push(&card);
         }
     }
 
-    fn decrypt_state(&mut self, state: FheUint8) {
-        let state_value: u8 = state.decrypt(self.key);
+    fn dealer_state(&self) -> FheState {
+        let points_for_player = self.cards_for_player.rate_cards();
+        let points_for_dealer = self.cards_for_dealer.rate_cards();
+
+        let ((dealer_must_hit, dealer_busts), game_over) = self.join(
+            || self.join(|| lt(&points_for_dealer, 17), || gt(&points_for_dealer, 21)),
+            || self.game_over(&points_for_dealer, &points_for_player),
+        );
 
-        self.state = SecureGameState::try_from(state_value).unwrap();
+        select(
+            &dealer_must_hit,
+            self.constants.get(SecureGameState::WaitingForDealer),
+            &select(
+                &dealer_busts,
+                self.constants.get(SecureGameState::DealerBusts),
+                &game_over,
+            ),
+        )
+    }
+
+    pub fn decide(&mut self, hit: &FheBool) {
+        self.start_action();
+
+        let card = self.deal_card();
+
+        self.cards_for_player.push_if(&card, hit);
+
+        if self.reveal == RevealPolicy::OutcomeOnly {
+            self.play_out_dealer(&!hit);
+        }
+
+        self.state = SecureGameState::Checking;
+
+        let (player_state, dealer_state) =
+            self.join(|| self.player_state(), || self.dealer_state());
+
+        let state = select(hit, &player_state, &dealer_state);
+
+        self.decrypt_state(state);
+        self.record_decision(hit);
+    }
+
+    fn decrypt_state(&mut self, state: FheState) {
+        let state = EncryptedState(state);
+
+        if let Some(key) = self.key {
+            self.state = match self.reveal {
+                RevealPolicy::EveryState => state.decrypt(key),
+                RevealPolicy::OutcomeOnly => {
+                    let is_over: bool = decrypt(&state.is_over(), key);
+
+                    if is_over {
+                        state.decrypt(key)
+                    } else {
+                        SecureGameState::WaitingForPlayer
+                    }
+                }
+            };
+        }
+
+        self.encrypted_state = Some(state);
+    }
+
+    pub fn decrypted_view(&self, key: &ClientKey) -> GameView {
+        GameView::new(
+            &self.transcript.rules(),
+            &self.cards_for_player.decrypt(key),
+            &self.cards_for_dealer.decrypt(key),
+            NaiveGameState::from(self.state),
+        )
     }
 
     pub fn dump_game(&self) {
-        dbg!(
-            decrypt_cards(&self.key, &self.cards_for_player),
-            decrypt_cards(&self.key, &self.cards_for_dealer),
-            &self.state
-        );
+        match self.key {
+            Some(key) => println!("{}", self.decrypted_view(key)),
+            None => println!("{}", self.redacted_view()),
+        }
+    }
+
+    pub fn enable_profiling(&mut self) {
+        self.profiler = Some(Profiler::new());
+    }
+
+    pub fn encrypted_state(&self) -> Option<&EncryptedState> {
+        self.encrypted_state.as_ref()
+    }
+
+    fn finish_action(&mut self, action: GameAction) {
+        if let Some(profiler) = &mut self.profiler {
+            profiler.finish_action(action);
+        }
+    }
+
+    pub fn for_server() -> Self {
+        Self {
+            reveal: RevealPolicy::OutcomeOnly,
+            ..Self::with_parts(None, next_seed())
+        }
     }
 
-    fn game_over(&self, points_for_dealer: &FheUint8, points_for_player: &FheUint8) -> FheUint8 {
-        points_for_dealer.gt(points_for_player).select(
-            &encrypt_state(self.key, SecureGameState::DealerWins),
-            &points_for_dealer.lt(points_for_player).select(
-                &encrypt_state(self.key, SecureGameState::PlayerWins),
-                &encrypt_state(self.key, SecureGameState::Tie),
+    fn game_over(&self, points_for_dealer: &FhePoints, points_for_player: &FhePoints) -> FheState {
+        let (dealer_leads, player_leads) = self.join(
+            || gt(points_for_dealer, points_for_player),
+            || lt(points_for_dealer, points_for_player),
+        );
+
+        select(
+            &dealer_leads,
+            self.constants.get(SecureGameState::DealerWins),
+            &select(
+                &player_leads,
+                self.constants.get(SecureGameState::PlayerWins),
+                self.constants.get(SecureGameState::Tie),
             ),
         )
     }
 
+    pub fn hand_record(&self, key: &ClientKey, hand_id: u64) -> HandRecord {
+        let entries = self.transcript.entries();
+
+        let actions: Vec<GameAction> = entries.iter().map(|entry| entry.action).collect();
+        let dealt_cards: Vec<&DealtCard> = entries.iter().flat_map(|entry| &entry.cards).collect();
+        let state = self
+            .transcript
+            .outcome()
+            .and_then(|state| SecureGameState::try_from(state).ok())
+            .map(NaiveGameState::from)
+            .unwrap_or(NaiveGameState::Uninitialized);
+        let rules = self.transcript.rules();
+
+        HandRecord {
+            actions: actions.clone(),
+            cards: dealt_cards
+                .iter()
+                .map(|dealt_card| {
+                    deserialize::<FheCard>(&dealt_card.ciphertext)
+                        .unwrap()
+                        .decrypt(key)
+                })
+                .collect(),
+            hand_id,
+            payout: payout(&rules, &actions, state),
+            rules,
+            seed: self.transcript.seed(),
+            state,
+        }
+    }
+
     pub fn hit_as_dealer(&mut self) {
+        self.start_action();
         self.deal_dealer(1);
         self.check_dealer();
+        self.record_action(GameAction::HitAsDealer);
     }
 
     pub fn hit_as_player(&mut self) {
+        self.start_action();
         self.deal_player(1);
         self.check_player();
+        self.record_action(GameAction::HitAsPlayer);
+    }
+
+    pub fn into_transcript(self) -> GameTranscript {
+        self.transcript
+    }
+
+    fn join<A, B, RA, RB>(&self, a: A, b: B) -> (RA, RB)
+    where
+        A: FnOnce() -> RA + Send,
+        B: FnOnce() -> RB + Send,
+        RA: Send,
+        RB: Send,
+    {
+        join_with_server_key(self.server_key.as_ref(), a, b)
+    }
+
+    pub fn load(key: &'info ClientKey, buffer: &[u8]) -> Result<Self, StorageError> {
+        let snapshot: SecureGameSnapshot = load_snapshot(buffer)?;
+
+        Ok(Self {
+            cards_for_dealer: SecureHand::load(&snapshot.cards_for_dealer)?,
+            cards_for_player: SecureHand::load(&snapshot.cards_for_player)?,
+            constants: TrivialStates::default(),
+            deck: snapshot
+                .deck
+                .iter()
+                .map(|buffer| deserialize(buffer))
+                .collect::<Result<_, _>>()?,
+            encrypted_state: snapshot
+                .encrypted_state
+                .map(|buffer| deserialize(&buffer).map(EncryptedState))
+                .transpose()?,
+            key: Some(key),
+            profiler: None,
+            reveal: snapshot.reveal,
+            seed: snapshot.seed,
+            server_key: None,
+            state: SecureGameState::try_from(snapshot.state).map_err(|_| StorageError::Corrupt)?,
+            transcript: snapshot.transcript,
+        })
     }
 
     pub fn new(key: &'info ClientKey) -> Self {
-        Self {
-            cards_for_dealer: vec![],
-            cards_for_player: vec![],
-            deck: vec![],
-            key: key,
-            state: SecureGameState::Uninitialized,
-        }
+        Self::with_parts(Some(key), next_seed())
+    }
+
+    pub fn parallelize(&mut self, server_key: &ServerKey) {
+        self.server_key = Some(server_key.clone());
     }
 
     pub fn plant_deck(&mut self, deck: &[u8]) {
         deck.iter().for_each(|&card_value| {
-            let card = encrypt_points(self.key, card_value);
+            let card = encrypt_card(self.key.unwrap(), card_value);
 
             self.deck.push(card);
         });
     }
 
-    fn random_card(&self) -> FheUint8 {
-        let seed = SEED_COUNTER.fetch_add(1, Ordering::Relaxed) as u128;
+    pub fn play(&mut self, action: GameAction) -> Result<(), ActionError> {
+        match action {
+            GameAction::CreateGame => self.create_game(),
+            GameAction::Decide => return Err(ActionError::DecisionRequired),
+            GameAction::HitAsDealer => self.hit_as_dealer(),
+            GameAction::HitAsPlayer => self.hit_as_player(),
+            GameAction::Stand => self.stand(),
+        }
+
+        Ok(())
+    }
+
+    fn play_out_dealer(&mut self, active: &FheBool) {
+        for _ in 0..DEALER_DRAWS {
+            let card = self.deal_card();
+            let must_hit = and(&lt(&self.cards_for_dealer.rate_cards(), 17), active);
 
-        FheUint8::div_rem(FheUint8::generate_oblivious_pseudo_random(Seed(seed)), 13).1
-            + encrypt_points(self.key, 2)
+            self.cards_for_dealer.push_if(&card, &must_hit);
+        }
     }
 
-    fn rate_card(&self, card: &FheUint8) -> FheUint8 {
-        card.lt(11).select(
-            card,
-            &card
-                .lt(14)
-                .select(&encrypt_points(self.key, 10), &encrypt_points(self.key, 11)),
+    fn player_state(&self) -> FheState {
+        let points_for_player = self.cards_for_player.rate_cards();
+
+        select(
+            &gt(&points_for_player, 21),
+            self.constants.get(SecureGameState::PlayerBusts),
+            self.constants.get(SecureGameState::WaitingForPlayer),
         )
     }
 
-    fn rate_cards(&self, cards: &[FheUint8]) -> FheUint8 {
-        let zero = encrypt_points(self.key, 0);
+    pub fn profile(&self) -> Option<&HandProfile> {
+        self.profiler.as_ref().map(Profiler::profile)
+    }
 
-        cards
-            .iter()
-            .fold(zero, |total, card| total + self.rate_card(card))
+    fn record_action(&mut self, action: GameAction) {
+        self.transcript.record_action(action, self.state as u8);
+        self.finish_action(action);
+    }
+
+    fn record_decision(&mut self, hit: &FheBool) {
+        self.transcript.record_decision(hit, self.state as u8);
+        self.finish_action(GameAction::Decide);
+    }
+
+    pub fn redacted_view(&self) -> RedactedView {
+        RedactedView {
+            cards_for_dealer: self.cards_for_dealer.ciphertext_size(),
+            cards_for_player: self.cards_for_player.ciphertext_size(),
+            state: self
+                .encrypted_state
+                .as_ref()
+                .map(|state| state_size(state.ciphertext())),
+        }
+    }
+
+    pub fn reveal_state(&mut self, state: SecureGameState) {
+        self.state = state;
+        self.transcript.reveal_state(state as u8);
+    }
+
+    pub fn save(&self) -> Vec<u8> {
+        save_snapshot(&SecureGameSnapshot {
+            cards_for_dealer: self.cards_for_dealer.save(),
+            cards_for_player: self.cards_for_player.save(),
+            deck: self.deck.iter().map(serialize).collect(),
+            encrypted_state: self
+                .encrypted_state
+                .as_ref()
+                .map(|state| serialize(state.ciphertext())),
+            reveal: self.reveal,
+            seed: self.seed,
+            state: self.state as u8,
+            transcript: self.transcript.clone(),
+        })
+    }
+
+    pub fn side_bets(&self) -> Option<EncryptedSideBets> {
+        if self.transcript.entries().first()?.action != GameAction::CreateGame {
+            return None;
+        }
+
+        let cards_for_player = self.cards_for_player.cards();
+        let dealer_up_card = &self.cards_for_dealer.cards()[0];
+
+        let (perfect_pairs, twenty_one_plus_three) = self.join(
+            || rate_encrypted_perfect_pairs(&cards_for_player[0], &cards_for_player[1]),
+            || {
+                rate_encrypted_twenty_one_plus_three([
+                    &cards_for_player[0],
+                    &cards_for_player[1],
+                    dealer_up_card,
+                ])
+            },
+        );
+
+        Some(EncryptedSideBets {
+            perfect_pairs,
+            twenty_one_plus_three,
+        })
     }
 
     pub fn stand(&mut self) {
+        self.start_action();
+
+        if self.reveal == RevealPolicy::OutcomeOnly {
+            self.play_out_dealer(&FheBool::encrypt_trivial(true));
+        }
+
         self.check_dealer();
+        self.record_action(GameAction::Stand);
+    }
+
+    fn start_action(&mut self) {
+        if let Some(profiler) = &mut self.profiler {
+            profiler.start_action();
+        }
+    }
+
+    pub fn state(&self) -> SecureGameState {
+        self.state
+    }
+
+    pub fn transcript(&self) -> &GameTranscript {
+        &self.transcript
+    }
+
+    fn with_parts(key: Option<&'info ClientKey>, seed: u128) -> Self {
+        Self {
+            cards_for_dealer: SecureHand::new(),
+            cards_for_player: SecureHand::new(),
+            constants: TrivialStates::default(),
+            deck: vec![],
+            encrypted_state: None,
+            key,
+            profiler: None,
+            reveal: RevealPolicy::EveryState,
+            seed,
+            server_key: None,
+            state: SecureGameState::Uninitialized,
+            transcript: GameTranscript::new(RuleSet::default(), seed),
+        }
     }
+
+    pub fn with_reveal_policy(key: &'info ClientKey, reveal: RevealPolicy) -> Self {
+        Self {
+            reveal,
+            ..Self::new(key)
+        }
+    }
+
+    pub fn with_seed(key: &'info ClientKey, seed: u128) -> Self {
+        Self::with_parts(Some(key), seed)
+    }
+}
+
+fn next_seed() -> u128 {
+    OsRng.unwrap_err().random()
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub enum RevealPolicy {
+    #[default]
+    EveryState,
+    OutcomeOnly,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum SecureGameState {
     Uninitialized,
     Checking,
@@ -212,12 +594,32 @@ impl TryFrom<u8> for SecureGameState {
     }
 }
 
+impl From<SecureGameState> for NaiveGameState {
+    fn from(state: SecureGameState) -> Self {
+        match state {
+            SecureGameState::Uninitialized => NaiveGameState::Uninitialized,
+            SecureGameState::Checking => NaiveGameState::Checking,
+            SecureGameState::DealerBusts => NaiveGameState::DealerBusts,
+            SecureGameState::DealerWins => NaiveGameState::DealerWins,
+            SecureGameState::PlayerBusts => NaiveGameState::PlayerBusts,
+            SecureGameState::PlayerWins => NaiveGameState::PlayerWins,
+            SecureGameState::Tie => NaiveGameState::Tie,
+            SecureGameState::WaitingForDealer => NaiveGameState::WaitingForDealer,
+            SecureGameState::WaitingForPlayer => NaiveGameState::WaitingForPlayer,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    use crate::game_transcript::audit_transcript;
+    use crate::playing_card::{PlayingCard, Suit};
+    use crate::secure_hand::HAND_CAPACITY;
+    use crate::side_bets::{PerfectPairs, SideBetPayouts, SideBets, TwentyOnePlusThree};
     use crate::tfhe_keys::initialize_keys;
-    use crate::tfhe_values::decrypt_cards;
+    use crate::tfhe_values::encrypt_decision;
 
     const J: u8 = 11;
     const Q: u8 = 12;
@@ -235,8 +637,8 @@ mod tests {
         game.plant_deck(&deck);
         game.create_game();
 
-        assert_eq!(vec!(6, 7), decrypt_cards(&client_key, &game.cards_for_player));
-        assert_eq!(vec!(8, 9), decrypt_cards(&client_key, &game.cards_for_dealer));
+        assert_eq!(vec!(6, 7), game.cards_for_player.decrypt(&client_key));
+        assert_eq!(vec!(8, 9), game.cards_for_dealer.decrypt(&client_key));
         assert_eq!(SecureGameState::WaitingForPlayer, game.state);
     }
 
@@ -251,8 +653,8 @@ mod tests {
         game.plant_deck(&deck);
         game.create_game();
 
-        assert_eq!(vec!(7, 8), decrypt_cards(&client_key, &game.cards_for_player));
-        assert_eq!(vec!(A, A), decrypt_cards(&client_key, &game.cards_for_dealer));
+        assert_eq!(vec!(7, 8), game.cards_for_player.decrypt(&client_key));
+        assert_eq!(vec!(A, A), game.cards_for_dealer.decrypt(&client_key));
         assert_eq!(SecureGameState::DealerBusts, game.state);
     }
 
@@ -275,8 +677,8 @@ mod tests {
 
         game.hit_as_dealer();
 
-        assert_eq!(vec!(7, 8), decrypt_cards(&client_key, &game.cards_for_player));
-        assert_eq!(vec!(7, 8, 9), decrypt_cards(&client_key, &game.cards_for_dealer));
+        assert_eq!(vec!(7, 8), game.cards_for_player.decrypt(&client_key));
+        assert_eq!(vec!(7, 8, 9), game.cards_for_dealer.decrypt(&client_key));
         assert_eq!(SecureGameState::DealerBusts, game.state);
     }
 
@@ -295,8 +697,8 @@ mod tests {
 
         game.stand();
 
-        assert_eq!(vec!(8, 9), decrypt_cards(&client_key, &game.cards_for_player));
-        assert_eq!(vec!(J, Q), decrypt_cards(&client_key, &game.cards_for_dealer));
+        assert_eq!(vec!(8, 9), game.cards_for_player.decrypt(&client_key));
+        assert_eq!(vec!(J, Q), game.cards_for_dealer.decrypt(&client_key));
         assert_eq!(SecureGameState::DealerWins, game.state);
     }
 
@@ -311,8 +713,8 @@ mod tests {
         game.plant_deck(&deck);
         game.create_game();
 
-        assert_eq!(vec!(6, 7), decrypt_cards(&client_key, &game.cards_for_player));
-        assert_eq!(vec!(K, A), decrypt_cards(&client_key, &game.cards_for_dealer));
+        assert_eq!(vec!(6, 7), game.cards_for_player.decrypt(&client_key));
+        assert_eq!(vec!(K, A), game.cards_for_dealer.decrypt(&client_key));
         assert_eq!(SecureGameState::DealerWins, game.state);
     }
 
@@ -335,11 +737,34 @@ mod tests {
 
         game.hit_as_dealer();
 
-        assert_eq!(vec!(J, Q), decrypt_cards(&client_key, &game.cards_for_player));
-        assert_eq!(vec!(6, 7, 8), decrypt_cards(&client_key, &game.cards_for_dealer));
+        assert_eq!(vec!(J, Q), game.cards_for_player.decrypt(&client_key));
+        assert_eq!(vec!(6, 7, 8), game.cards_for_dealer.decrypt(&client_key));
         assert_eq!(SecureGameState::DealerWins, game.state);
     }
 
+    #[test]
+    fn export_hand_record() {
+        let (client_key, _) = initialize_keys();
+
+        let mut game = SecureGame::with_seed(&client_key, 5);
+
+        game.plant_deck(&[Q, J, 9, 8]);
+        game.create_game();
+        game.stand();
+
+        let record = game.hand_record(&client_key, 7);
+
+        assert_eq!(
+            vec!(GameAction::CreateGame, GameAction::Stand),
+            record.actions
+        );
+        assert_eq!(vec!(8, 9, J, Q), record.cards);
+        assert_eq!(-1.0, record.payout);
+        assert_eq!(RuleSet::default(), record.rules);
+        assert_eq!(5, record.seed);
+        assert_eq!(NaiveGameState::DealerWins, record.state);
+    }
+
     #[test]
     fn game_ends_in_a_tie() {
         let (client_key, _) = initialize_keys();
@@ -355,11 +780,86 @@ mod tests {
 
         game.stand();
 
-        assert_eq!(vec!(8, 9), decrypt_cards(&client_key, &game.cards_for_player));
-        assert_eq!(vec!(8, 9), decrypt_cards(&client_key, &game.cards_for_dealer));
+        assert_eq!(vec!(8, 9), game.cards_for_player.decrypt(&client_key));
+        assert_eq!(vec!(8, 9), game.cards_for_dealer.decrypt(&client_key));
         assert_eq!(SecureGameState::Tie, game.state);
     }
 
+    #[test]
+    fn hidden_decisions() {
+        let (client_key, _) = initialize_keys();
+
+        let mut game = SecureGame::new(&client_key);
+
+        let deck = vec![5, 2, 9, 8, 7, 6];
+
+        game.plant_deck(&deck);
+        game.create_game();
+        game.decide(&encrypt_decision(&client_key, true));
+
+        assert_eq!(SecureGameState::WaitingForPlayer, game.state);
+
+        game.decide(&encrypt_decision(&client_key, false));
+
+        assert_eq!(HAND_CAPACITY, game.cards_for_player.cards().len());
+        assert_eq!(vec!(6, 7, 2), game.cards_for_player.decrypt(&client_key));
+        assert_eq!(vec!(8, 9), game.cards_for_dealer.decrypt(&client_key));
+        assert_eq!(SecureGameState::DealerWins, game.state);
+        assert_eq!(
+            Ok(()),
+            audit_transcript(&client_key, &game.transcript, &game.transcript.commitment())
+        );
+    }
+
+    #[test]
+    fn outcome_only() {
+        let (client_key, _) = initialize_keys();
+
+        let mut game = SecureGame::with_reveal_policy(&client_key, RevealPolicy::OutcomeOnly);
+
+        let deck = vec![10, 5, 9, 4, 7, 6];
+
+        game.plant_deck(&deck);
+        game.create_game();
+
+        assert_eq!(SecureGameState::WaitingForPlayer, game.state);
+
+        game.stand();
+
+        assert_eq!(vec!(6, 7), game.cards_for_player.decrypt(&client_key));
+        assert_eq!(vec!(4, 9, 5), game.cards_for_dealer.decrypt(&client_key));
+        assert_eq!(SecureGameState::DealerWins, game.state);
+        assert_eq!(
+            SecureGameState::DealerWins,
+            game.encrypted_state().unwrap().decrypt(&client_key)
+        );
+        assert_eq!(
+            Ok(()),
+            audit_transcript(&client_key, &game.transcript, &game.transcript.commitment())
+        );
+    }
+
+    #[test]
+    fn parallel_checks() {
+        let (client_key, server_key) = initialize_keys();
+
+        let mut game = SecureGame::new(&client_key);
+
+        let deck = vec![5, 2, 9, 8, 7, 6];
+
+        game.parallelize(&server_key);
+        game.plant_deck(&deck);
+        game.create_game();
+
+        assert_eq!(SecureGameState::WaitingForPlayer, game.state);
+
+        game.decide(&encrypt_decision(&client_key, false));
+
+        assert_eq!(vec!(6, 7), game.cards_for_player.decrypt(&client_key));
+        assert_eq!(vec!(8, 9), game.cards_for_dealer.decrypt(&client_key));
+        assert_eq!(SecureGameState::DealerWins, game.state);
+    }
+
     #[test]
     fn player_busts_early() {
         let (client_key, _) = initialize_keys();
@@ -371,8 +871,8 @@ mod tests {
         game.plant_deck(&deck);
         game.create_game();
 
-        assert_eq!(vec!(A, A), decrypt_cards(&client_key, &game.cards_for_player));
-        assert_eq!(vec!(7, 8), decrypt_cards(&client_key, &game.cards_for_dealer));
+        assert_eq!(vec!(A, A), game.cards_for_player.decrypt(&client_key));
+        assert_eq!(vec!(7, 8), game.cards_for_dealer.decrypt(&client_key));
         assert_eq!(SecureGameState::PlayerBusts, game.state);
     }
 
@@ -391,8 +891,8 @@ mod tests {
 
         game.hit_as_player();
 
-        assert_eq!(vec!(7, 8, 9), decrypt_cards(&client_key, &game.cards_for_player));
-        assert_eq!(vec!(7, 8), decrypt_cards(&client_key, &game.cards_for_dealer));
+        assert_eq!(vec!(7, 8, 9), game.cards_for_player.decrypt(&client_key));
+        assert_eq!(vec!(7, 8), game.cards_for_dealer.decrypt(&client_key));
         assert_eq!(SecureGameState::PlayerBusts, game.state);
     }
 
@@ -411,8 +911,8 @@ mod tests {
 
         game.stand();
 
-        assert_eq!(vec!(J, Q), decrypt_cards(&client_key, &game.cards_for_player));
-        assert_eq!(vec!(8, 9), decrypt_cards(&client_key, &game.cards_for_dealer));
+        assert_eq!(vec!(J, Q), game.cards_for_player.decrypt(&client_key));
+        assert_eq!(vec!(8, 9), game.cards_for_dealer.decrypt(&client_key));
         assert_eq!(SecureGameState::PlayerWins, game.state);
     }
 
@@ -427,8 +927,8 @@ mod tests {
         game.plant_deck(&deck);
         game.create_game();
 
-        assert_eq!(vec!(K, A), decrypt_cards(&client_key, &game.cards_for_player));
-        assert_eq!(vec!(6, 7), decrypt_cards(&client_key, &game.cards_for_dealer));
+        assert_eq!(vec!(K, A), game.cards_for_player.decrypt(&client_key));
+        assert_eq!(vec!(6, 7), game.cards_for_dealer.decrypt(&client_key));
         assert_eq!(SecureGameState::PlayerWins, game.state);
     }
 
@@ -451,8 +951,117 @@ mod tests {
 
         game.stand();
 
-        assert_eq!(vec!(6, 7, 8), decrypt_cards(&client_key, &game.cards_for_player));
-        assert_eq!(vec!(J, Q), decrypt_cards(&client_key, &game.cards_for_dealer));
+        assert_eq!(vec!(6, 7, 8), game.cards_for_player.decrypt(&client_key));
+        assert_eq!(vec!(J, Q), game.cards_for_dealer.decrypt(&client_key));
         assert_eq!(SecureGameState::PlayerWins, game.state);
     }
+
+    #[cfg(feature = "profile")]
+    #[test]
+    fn profile_game() {
+        let (client_key, _) = initialize_keys();
+
+        let mut game = SecureGame::new(&client_key);
+
+        game.enable_profiling();
+        game.plant_deck(&[6, 6, 6, 6, 6]);
+        game.create_game();
+        game.hit_as_player();
+
+        let costs = &game.profile().unwrap().actions;
+
+        assert_eq!(GameAction::CreateGame, costs[0].action);
+        assert_eq!(GameAction::HitAsPlayer, costs[1].action);
+        assert!(costs.iter().all(|cost| cost.operations.bootstraps > 0));
+        assert!(costs[0].operations.encryptions >= 5);
+        assert!(costs[0].operations.lookups >= 4);
+        assert!(costs[0].operations.selects >= 4 * HAND_CAPACITY as u64);
+        assert!(costs[1].operations.selects >= HAND_CAPACITY as u64);
+    }
+
+    #[test]
+    fn rate_side_bets() {
+        let (client_key, _) = initialize_keys();
+
+        let mut game = SecureGame::new(&client_key);
+
+        let deck = [
+            (10, Suit::Clubs),
+            (7, Suit::Diamonds),
+            (7, Suit::Hearts),
+            (7, Suit::Hearts),
+        ]
+        .map(|(rank, suit)| PlayingCard::new(rank, suit).encode());
+
+        game.plant_deck(&deck);
+
+        assert!(game.side_bets().is_none());
+
+        game.create_game();
+
+        assert_eq!(
+            SideBets {
+                perfect_pairs: PerfectPairs::PerfectPair,
+                perfect_pairs_payout: 25,
+                twenty_one_plus_three: TwentyOnePlusThree::ThreeOfAKind,
+                twenty_one_plus_three_payout: 30,
+            },
+            game.side_bets()
+                .unwrap()
+                .decrypt(&client_key, &SideBetPayouts::default())
+        );
+    }
+
+    #[test]
+    fn resume_game() {
+        let (client_key, _) = initialize_keys();
+
+        let mut game = SecureGame::new(&client_key);
+
+        let deck = vec![8, Q, J, 7, 6];
+
+        game.plant_deck(&deck);
+        game.create_game();
+
+        let mut resumed_game = SecureGame::load(&client_key, &game.save()).unwrap();
+
+        assert_eq!(SecureGameState::WaitingForPlayer, resumed_game.state);
+
+        resumed_game.hit_as_player();
+        resumed_game.stand();
+
+        assert_eq!(
+            vec!(6, 7, 8),
+            resumed_game.cards_for_player.decrypt(&client_key)
+        );
+        assert_eq!(
+            vec!(J, Q),
+            resumed_game.cards_for_dealer.decrypt(&client_key)
+        );
+        assert_eq!(SecureGameState::PlayerWins, resumed_game.state);
+        assert_eq!(3, resumed_game.transcript.entries().len());
+    }
+
+    #[test]
+    fn view_game() {
+        let (client_key, _) = initialize_keys();
+
+        let mut game = SecureGame::new(&client_key);
+
+        game.plant_deck(&[9, 8, 7, 6]);
+        game.create_game();
+
+        let redacted = game.redacted_view();
+
+        assert_eq!(
+            "Player: 6♣ 7♣ (13 hard)  Dealer: 8♣ 9♣ (17 hard)  WaitingForPlayer",
+            game.decrypted_view(&client_key).to_string()
+        );
+        assert_eq!(redacted.cards_for_dealer, redacted.cards_for_player);
+        assert_eq!(
+            serialize(game.encrypted_state.as_ref().unwrap().ciphertext()).len(),
+            redacted.state.unwrap()
+        );
+        assert!(!format!("{:?}", game).contains('♣'));
+    }
 }
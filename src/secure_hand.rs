@@ -0,0 +1,190 @@
+use crate::game_storage::StorageError;
+use crate::secure_profile::{and, eq, lt, scalar_select, select};
+use crate::tfhe_values::{
+    card_size, deserialize, points_size, rate_card, serialize, trivial_card, trivial_points,
+    FheCard, FhePoints,
+};
+
+use serde::{Deserialize, Serialize};
+use tfhe::prelude::{CastFrom, FheDecrypt, FheTrivialEncrypt};
+use tfhe::{ClientKey, FheBool};
+
+pub const HAND_CAPACITY: usize = 11;
+
+#[derive(Clone)]
+pub struct SecureHand {
+    cards: Vec<FheCard>,
+    count: FheCard,
+    total: FhePoints,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct HandSnapshot {
+    cards: Vec<Vec<u8>>,
+    count: Vec<u8>,
+    total: Vec<u8>,
+}
+
+impl Default for SecureHand {
+    fn default() -> Self {
+        Self::with_capacity(HAND_CAPACITY)
+    }
+}
+
+impl SecureHand {
+    pub fn cards(&self) -> &[FheCard] {
+        &self.cards
+    }
+
+    pub fn ciphertext_size(&self) -> usize {
+        (self.cards.len() + 1) * card_size(&self.count) + points_size(&self.total)
+    }
+
+    pub fn count(&self) -> &FheCard {
+        &self.count
+    }
+
+    pub fn decrypt(&self, key: &ClientKey) -> Vec<u8> {
+        let count: u8 = self.count.decrypt(key);
+
+        self.cards
+            .iter()
+            .take(count as usize)
+            .map(|card| card.decrypt(key))
+            .collect()
+    }
+
+    pub fn load(snapshot: &HandSnapshot) -> Result<Self, StorageError> {
+        Ok(Self {
+            cards: snapshot
+                .cards
+                .iter()
+                .map(|buffer| deserialize(buffer))
+                .collect::<Result<_, _>>()?,
+            count: deserialize(&snapshot.count)?,
+            total: deserialize(&snapshot.total)?,
+        })
+    }
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, card: &FheCard) {
+        self.push_if(card, &FheBool::encrypt_trivial(true));
+    }
+
+    pub fn push_if(&mut self, card: &FheCard, condition: &FheBool) {
+        for (index, slot) in self.cards.iter_mut().enumerate() {
+            let is_next = and(&eq(&self.count, index as u8), condition);
+
+            *slot = select(&is_next, card, slot);
+        }
+
+        let pushed = and(&lt(&self.count, self.cards.len() as u8), condition);
+
+        self.count += FheCard::cast_from(pushed.clone());
+        self.total += scalar_select(&pushed, &rate_card(card), 0u8);
+    }
+
+    pub fn rate_cards(&self) -> FhePoints {
+        self.total.clone()
+    }
+
+    pub fn save(&self) -> HandSnapshot {
+        HandSnapshot {
+            cards: self.cards.iter().map(serialize).collect(),
+            count: serialize(&self.count),
+            total: serialize(&self.total),
+        }
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            cards: (0..capacity).map(|_| trivial_card(0)).collect(),
+            count: trivial_card(0),
+            total: trivial_points(0),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::playing_card::{PlayingCard, Suit};
+    use crate::tfhe_keys::initialize_keys;
+    use crate::tfhe_values::{
+        decrypt_playing_cards, encrypt_card, encrypt_decision, encrypt_playing_card,
+    };
+
+    #[test]
+    fn drop_cards_beyond_capacity() {
+        let (client_key, _) = initialize_keys();
+
+        let mut hand = SecureHand::with_capacity(2);
+
+        for card in [9, 5, 14] {
+            hand.push(&encrypt_card(&client_key, card));
+        }
+
+        let count: u8 = hand.count().decrypt(&client_key);
+        let points: u8 = hand.rate_cards().decrypt(&client_key);
+
+        let snapshot = hand.save();
+
+        assert_eq!(2, count);
+        assert_eq!(vec!(9, 5), hand.decrypt(&client_key));
+        assert_eq!(14, points);
+        assert_eq!(
+            snapshot.cards.iter().map(Vec::len).sum::<usize>()
+                + snapshot.count.len()
+                + snapshot.total.len(),
+            hand.ciphertext_size()
+        );
+    }
+
+    #[test]
+    fn rate_suited_cards() {
+        let (client_key, _) = initialize_keys();
+
+        let cards = vec![
+            PlayingCard::new(14, Suit::Spades),
+            PlayingCard::new(13, Suit::Hearts),
+        ];
+
+        let mut hand = SecureHand::with_capacity(2);
+
+        for card in &cards {
+            hand.push(&encrypt_playing_card(&client_key, card));
+        }
+
+        let points: u8 = hand.rate_cards().decrypt(&client_key);
+
+        assert_eq!(cards, decrypt_playing_cards(&client_key, hand.cards()));
+        assert_eq!(21, points);
+    }
+
+    #[test]
+    fn rate_valid_slots_only() {
+        let (client_key, _) = initialize_keys();
+
+        let mut hand = SecureHand::with_capacity(4);
+
+        hand.push(&encrypt_card(&client_key, 9));
+        hand.push_if(
+            &encrypt_card(&client_key, 5),
+            &encrypt_decision(&client_key, false),
+        );
+        hand.push_if(
+            &encrypt_card(&client_key, 14),
+            &encrypt_decision(&client_key, true),
+        );
+
+        let points: u8 = hand.rate_cards().decrypt(&client_key);
+
+        assert_eq!(4, hand.cards().len());
+        assert_eq!(vec!(9, 14), hand.decrypt(&client_key));
+        assert_eq!(20, points);
+    }
+}
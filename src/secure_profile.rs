@@ -0,0 +1,283 @@
+use crate::game_transcript::GameAction;
+
+use std::fmt;
+use std::ops::{Add, Sub};
+#[cfg(feature = "profile")]
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+use tfhe::prelude::{FheDecrypt, FheEq, FheOrd, IfThenElse, ScalarIfThenElse};
+use tfhe::{ClientKey, FheBool};
+
+#[cfg(feature = "profile")]
+static COMPARISONS: AtomicU64 = AtomicU64::new(0);
+#[cfg(feature = "profile")]
+static DECRYPTIONS: AtomicU64 = AtomicU64::new(0);
+#[cfg(feature = "profile")]
+static ENCRYPTIONS: AtomicU64 = AtomicU64::new(0);
+#[cfg(feature = "profile")]
+static LOGIC: AtomicU64 = AtomicU64::new(0);
+#[cfg(feature = "profile")]
+static LOOKUPS: AtomicU64 = AtomicU64::new(0);
+#[cfg(feature = "profile")]
+static SELECTS: AtomicU64 = AtomicU64::new(0);
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ActionCost {
+    pub action: GameAction,
+    pub operations: OperationCounts,
+    pub wall_time: Duration,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct HandProfile {
+    pub actions: Vec<ActionCost>,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum Operation {
+    Comparison,
+    Decryption,
+    Encryption,
+    Logic,
+    Lookup,
+    Select,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct OperationCounts {
+    pub bootstraps: u64,
+    pub comparisons: u64,
+    pub decryptions: u64,
+    pub encryptions: u64,
+    pub logic: u64,
+    pub lookups: u64,
+    pub selects: u64,
+}
+
+pub struct Profiler {
+    counts_mark: OperationCounts,
+    profile: HandProfile,
+    time_mark: Instant,
+}
+
+impl fmt::Display for HandProfile {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            formatter,
+            "{:<12} {:>10} {:>10} {:>11} {:>7} {:>7} {:>7} {:>11} {:>11}",
+            "action",
+            "wall ms",
+            "bootstraps",
+            "comparisons",
+            "selects",
+            "logic",
+            "lookups",
+            "encryptions",
+            "decryptions"
+        )?;
+
+        let row = |formatter: &mut fmt::Formatter,
+                   name: &str,
+                   wall_time: Duration,
+                   counts: OperationCounts| {
+            writeln!(
+                formatter,
+                "{:<12} {:>10.1} {:>10} {:>11} {:>7} {:>7} {:>7} {:>11} {:>11}",
+                name,
+                wall_time.as_secs_f64() * 1000.0,
+                counts.bootstraps,
+                counts.comparisons,
+                counts.selects,
+                counts.logic,
+                counts.lookups,
+                counts.encryptions,
+                counts.decryptions
+            )
+        };
+
+        for cost in &self.actions {
+            row(
+                formatter,
+                &format!("{:?}", cost.action),
+                cost.wall_time,
+                cost.operations,
+            )?;
+        }
+
+        let (wall_time, counts) = self.total();
+
+        row(formatter, "total", wall_time, counts)
+    }
+}
+
+impl HandProfile {
+    pub fn total(&self) -> (Duration, OperationCounts) {
+        self.actions.iter().fold(
+            (Duration::ZERO, OperationCounts::default()),
+            |(wall_time, counts), cost| (wall_time + cost.wall_time, counts + cost.operations),
+        )
+    }
+}
+
+impl Add for OperationCounts {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self {
+            bootstraps: self.bootstraps + other.bootstraps,
+            comparisons: self.comparisons + other.comparisons,
+            decryptions: self.decryptions + other.decryptions,
+            encryptions: self.encryptions + other.encryptions,
+            logic: self.logic + other.logic,
+            lookups: self.lookups + other.lookups,
+            selects: self.selects + other.selects,
+        }
+    }
+}
+
+impl Sub for OperationCounts {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Self {
+            bootstraps: self.bootstraps.saturating_sub(other.bootstraps),
+            comparisons: self.comparisons.saturating_sub(other.comparisons),
+            decryptions: self.decryptions.saturating_sub(other.decryptions),
+            encryptions: self.encryptions.saturating_sub(other.encryptions),
+            logic: self.logic.saturating_sub(other.logic),
+            lookups: self.lookups.saturating_sub(other.lookups),
+            selects: self.selects.saturating_sub(other.selects),
+        }
+    }
+}
+
+impl Default for Profiler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Profiler {
+    pub fn finish_action(&mut self, action: GameAction) {
+        let counts = counts();
+
+        self.profile.actions.push(ActionCost {
+            action,
+            operations: counts - self.counts_mark,
+            wall_time: self.time_mark.elapsed(),
+        });
+
+        self.counts_mark = counts;
+    }
+
+    pub fn new() -> Self {
+        Self {
+            counts_mark: counts(),
+            profile: HandProfile::default(),
+            time_mark: Instant::now(),
+        }
+    }
+
+    pub fn profile(&self) -> &HandProfile {
+        &self.profile
+    }
+
+    pub fn start_action(&mut self) {
+        self.time_mark = Instant::now();
+    }
+}
+
+pub fn and(lhs: &FheBool, rhs: &FheBool) -> FheBool {
+    count(Operation::Logic, 1);
+
+    lhs & rhs
+}
+
+#[cfg(feature = "profile")]
+pub fn count(operation: Operation, times: usize) {
+    let counter = match operation {
+        Operation::Comparison => &COMPARISONS,
+        Operation::Decryption => &DECRYPTIONS,
+        Operation::Encryption => &ENCRYPTIONS,
+        Operation::Logic => &LOGIC,
+        Operation::Lookup => &LOOKUPS,
+        Operation::Select => &SELECTS,
+    };
+
+    counter.fetch_add(times as u64, Ordering::Relaxed);
+}
+
+#[cfg(not(feature = "profile"))]
+pub fn count(_operation: Operation, _times: usize) {}
+
+#[cfg(feature = "profile")]
+pub fn counts() -> OperationCounts {
+    OperationCounts {
+        bootstraps: tfhe::get_pbs_count(),
+        comparisons: COMPARISONS.load(Ordering::Relaxed),
+        decryptions: DECRYPTIONS.load(Ordering::Relaxed),
+        encryptions: ENCRYPTIONS.load(Ordering::Relaxed),
+        logic: LOGIC.load(Ordering::Relaxed),
+        lookups: LOOKUPS.load(Ordering::Relaxed),
+        selects: SELECTS.load(Ordering::Relaxed),
+    }
+}
+
+#[cfg(not(feature = "profile"))]
+pub fn counts() -> OperationCounts {
+    OperationCounts::default()
+}
+
+pub fn decrypt<Clear, Ciphertext: FheDecrypt<Clear>>(
+    ciphertext: &Ciphertext,
+    key: &ClientKey,
+) -> Clear {
+    count(Operation::Decryption, 1);
+
+    ciphertext.decrypt(key)
+}
+
+pub fn eq<Lhs: FheEq<Rhs>, Rhs>(lhs: &Lhs, rhs: Rhs) -> FheBool {
+    count(Operation::Comparison, 1);
+
+    lhs.eq(rhs)
+}
+
+pub fn gt<Lhs: FheOrd<Rhs>, Rhs>(lhs: &Lhs, rhs: Rhs) -> FheBool {
+    count(Operation::Comparison, 1);
+
+    lhs.gt(rhs)
+}
+
+pub fn lt<Lhs: FheOrd<Rhs>, Rhs>(lhs: &Lhs, rhs: Rhs) -> FheBool {
+    count(Operation::Comparison, 1);
+
+    lhs.lt(rhs)
+}
+
+pub fn scalar_select<Lhs, Rhs>(
+    condition: &FheBool,
+    when_true: Lhs,
+    when_false: Rhs,
+) -> <FheBool as ScalarIfThenElse<Lhs, Rhs>>::Output
+where
+    FheBool: ScalarIfThenElse<Lhs, Rhs>,
+{
+    count(Operation::Select, 1);
+
+    condition.scalar_select(when_true, when_false)
+}
+
+pub fn select<Ciphertext>(
+    condition: &FheBool,
+    when_true: &Ciphertext,
+    when_false: &Ciphertext,
+) -> Ciphertext
+where
+    FheBool: IfThenElse<Ciphertext>,
+{
+    count(Operation::Select, 1);
+
+    condition.select(when_true, when_false)
+}
@@ -0,0 +1,83 @@
+use crate::game_transcript::GameAction;
+use crate::secure_hand::HandSnapshot;
+
+use std::io::{Error, ErrorKind, Read, Result, Write};
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+pub const DEFAULT_ADDRESS: &str = "127.0.0.1:7878";
+
+pub const MAX_FRAME_LEN: u64 = 1 << 28;
+
+#[derive(Deserialize, Serialize)]
+pub enum ClientMessage {
+    Action(GameAction),
+    Decide(Vec<u8>),
+    NewHand,
+    RegisterKey(Vec<u8>),
+    RevealState(u8),
+}
+
+#[derive(Deserialize, Serialize)]
+pub enum ServerMessage {
+    EncryptedResult(EncryptedResult),
+    Error(String),
+    KeyRegistered,
+    StateRevealed,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct EncryptedResult {
+    pub cards_for_dealer: HandSnapshot,
+    pub cards_for_player: HandSnapshot,
+    pub commitment: [u8; 32],
+    pub state: Vec<u8>,
+}
+
+pub fn read_message<T: DeserializeOwned>(reader: &mut impl Read) -> Result<T> {
+    let mut length = [0; 8];
+
+    reader.read_exact(&mut length)?;
+
+    let length = u64::from_le_bytes(length);
+
+    if length > MAX_FRAME_LEN {
+        return Err(Error::new(ErrorKind::InvalidData, "Frame too long"));
+    }
+
+    let mut buffer = vec![0; length as usize];
+
+    reader.read_exact(&mut buffer)?;
+
+    bincode::deserialize(&buffer).map_err(|error| Error::new(ErrorKind::InvalidData, error))
+}
+
+pub fn write_message<T: Serialize>(writer: &mut impl Write, message: &T) -> Result<()> {
+    let buffer =
+        bincode::serialize(message).map_err(|error| Error::new(ErrorKind::InvalidData, error))?;
+
+    writer.write_all(&(buffer.len() as u64).to_le_bytes())?;
+    writer.write_all(&buffer)?;
+    writer.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::io::Cursor;
+
+    #[test]
+    fn reject_long_frames() {
+        let mut frame = (MAX_FRAME_LEN + 1).to_le_bytes().to_vec();
+
+        frame.push(0);
+
+        let error = read_message::<ClientMessage>(&mut Cursor::new(frame))
+            .err()
+            .unwrap();
+
+        assert_eq!(ErrorKind::InvalidData, error.kind());
+    }
+}
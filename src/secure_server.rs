@@ -0,0 +1,268 @@
+use crate::game_transcript::{is_legal, ActionError, GameAction, GameTranscript};
+use crate::secure_game::{SecureGame, SecureGameState};
+use crate::secure_protocol::{
+    read_message, write_message, ClientMessage, EncryptedResult, ServerMessage,
+};
+use crate::tfhe_keys::SERVER_KEY_SIZE_LIMIT;
+use crate::tfhe_values::{deserialize, serialize};
+
+use std::io::{Cursor, ErrorKind, Result};
+use std::net::TcpStream;
+
+use tfhe::safe_serialization::safe_deserialize;
+use tfhe::{set_server_key, FheBool, ServerKey};
+
+pub fn serve_client(mut stream: TcpStream) -> Result<Vec<GameTranscript>> {
+    let mut game: Option<SecureGame> = None;
+    let mut server_key: Option<ServerKey> = None;
+    let mut transcripts = Vec::new();
+
+    loop {
+        let message = match read_message(&mut stream) {
+            Ok(message) => message,
+            Err(error) if error.kind() == ErrorKind::UnexpectedEof => {
+                transcripts.extend(game.map(SecureGame::into_transcript));
+
+                return Ok(transcripts);
+            }
+            Err(error) => return Err(error),
+        };
+
+        let response = match message {
+            ClientMessage::Action(GameAction::CreateGame) => {
+                ServerMessage::Error("Use a new hand to create a game".to_string())
+            }
+            ClientMessage::Action(action) => match game.as_mut() {
+                Some(game) if !is_legal(action, game.state() as u8) => illegal_action(game),
+                Some(game) => match game.play(action) {
+                    Ok(()) => encrypted_result(game),
+                    Err(ActionError::DecisionRequired) => {
+                        ServerMessage::Error("Use an encrypted decision to decide".to_string())
+                    }
+                },
+                None => ServerMessage::Error("No hand in progress".to_string()),
+            },
+            ClientMessage::Decide(buffer) => match (game.as_mut(), deserialize::<FheBool>(&buffer))
+            {
+                (Some(game), _) if !is_legal(GameAction::Decide, game.state() as u8) => {
+                    illegal_action(game)
+                }
+                (Some(game), Ok(hit)) => {
+                    game.decide(&hit);
+
+                    encrypted_result(game)
+                }
+                (None, _) => ServerMessage::Error("No hand in progress".to_string()),
+                (_, Err(_)) => ServerMessage::Error("Corrupt decision".to_string()),
+            },
+            ClientMessage::NewHand => match (&server_key, &game) {
+                (None, _) => ServerMessage::Error("No server key registered".to_string()),
+                (_, Some(game)) if !is_over(game.state()) => {
+                    ServerMessage::Error("Hand in progress".to_string())
+                }
+                (Some(server_key), _) => {
+                    let mut new_game = SecureGame::for_server();
+
+                    new_game.parallelize(server_key);
+                    new_game.create_game();
+
+                    let response = encrypted_result(&new_game);
+
+                    transcripts.extend(game.replace(new_game).map(SecureGame::into_transcript));
+
+                    response
+                }
+            },
+            ClientMessage::RegisterKey(buffer) => {
+                match safe_deserialize::<ServerKey>(Cursor::new(buffer), SERVER_KEY_SIZE_LIMIT) {
+                    Ok(registered_key) => {
+                        set_server_key(registered_key.clone());
+
+                        server_key = Some(registered_key);
+
+                        ServerMessage::KeyRegistered
+                    }
+                    Err(_) => ServerMessage::Error("Corrupt server key".to_string()),
+                }
+            }
+            ClientMessage::RevealState(state) => {
+                match (game.as_mut(), SecureGameState::try_from(state)) {
+                    (None, _) => ServerMessage::Error("No hand in progress".to_string()),
+                    (Some(game), _) if game.state() != SecureGameState::Checking => {
+                        ServerMessage::Error("No state to reveal".to_string())
+                    }
+                    (Some(game), Ok(state)) if is_revealable(state) => {
+                        game.reveal_state(state);
+
+                        ServerMessage::StateRevealed
+                    }
+                    (Some(_), _) => ServerMessage::Error("Corrupt state".to_string()),
+                }
+            }
+        };
+
+        write_message(&mut stream, &response)?;
+    }
+}
+
+fn encrypted_result(game: &SecureGame) -> ServerMessage {
+    ServerMessage::EncryptedResult(EncryptedResult {
+        cards_for_dealer: game.cards_for_dealer().save(),
+        cards_for_player: game.cards_for_player().save(),
+        commitment: game.transcript().commitment(),
+        state: serialize(game.encrypted_state().unwrap().ciphertext()),
+    })
+}
+
+fn illegal_action(game: &SecureGame) -> ServerMessage {
+    match game.state() {
+        SecureGameState::Checking => ServerMessage::Error("State not revealed".to_string()),
+        state => ServerMessage::Error(format!("Illegal action while {:?}", state)),
+    }
+}
+
+fn is_over(state: SecureGameState) -> bool {
+    !matches!(
+        state,
+        SecureGameState::Checking
+            | SecureGameState::WaitingForDealer
+            | SecureGameState::WaitingForPlayer
+    )
+}
+
+fn is_revealable(state: SecureGameState) -> bool {
+    !matches!(
+        state,
+        SecureGameState::Uninitialized | SecureGameState::Checking
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::game_transcript::{audit_transcript, AuditError};
+    use crate::naive_game::NaiveGame;
+    use crate::secure_client::SecureClient;
+    use crate::tfhe_keys::initialize_keys;
+    use crate::tfhe_values::FheState;
+
+    use std::net::TcpListener;
+    use std::thread;
+
+    use tfhe::prelude::FheDecrypt;
+    use tfhe::safe_serialization::safe_serialize;
+
+    fn request(stream: &mut TcpStream, message: ClientMessage) -> ServerMessage {
+        write_message(stream, &message).unwrap();
+
+        read_message(stream).unwrap()
+    }
+
+    #[test]
+    fn audit_dishonest_client() {
+        let (client_key, server_key) = initialize_keys();
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+
+        let server = thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+
+            serve_client(stream).unwrap()
+        });
+
+        let mut stream = TcpStream::connect(address).unwrap();
+        let mut buffer = Vec::new();
+
+        safe_serialize(&server_key, &mut buffer, u64::MAX).unwrap();
+
+        assert!(matches!(
+            request(&mut stream, ClientMessage::RegisterKey(buffer)),
+            ServerMessage::KeyRegistered
+        ));
+
+        let commitment = loop {
+            let result = match request(&mut stream, ClientMessage::NewHand) {
+                ServerMessage::EncryptedResult(result) => result,
+                _ => panic!("Expected an encrypted result"),
+            };
+
+            let state: u8 = deserialize::<FheState>(&result.state)
+                .unwrap()
+                .decrypt(&client_key);
+            let claimed_state = SecureGameState::PlayerWins as u8;
+
+            if state != claimed_state {
+                assert!(matches!(
+                    request(&mut stream, ClientMessage::RevealState(claimed_state)),
+                    ServerMessage::StateRevealed
+                ));
+
+                break result.commitment;
+            }
+
+            request(&mut stream, ClientMessage::RevealState(state));
+        };
+
+        drop(stream);
+
+        let transcripts = server.join().unwrap();
+
+        assert_eq!(
+            Err(AuditError::StateMismatch(0)),
+            audit_transcript(&client_key, transcripts.last().unwrap(), &commitment)
+        );
+    }
+
+    #[test]
+    fn play_over_socket() {
+        let (client_key, server_key) = initialize_keys();
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+
+        let server = thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+
+            serve_client(stream).unwrap();
+        });
+
+        let mut client = SecureClient::connect(&client_key, &address).unwrap();
+
+        assert!(client.new_hand().is_err());
+
+        client.register_key(&server_key).unwrap();
+
+        let mut result = client.new_hand().unwrap();
+
+        assert_ne!([0; 32], result.commitment);
+
+        let mut game = NaiveGame::new(0);
+
+        game.plant_deck(&[
+            result.cards_for_dealer[1],
+            result.cards_for_dealer[0],
+            result.cards_for_player[1],
+            result.cards_for_player[0],
+        ]);
+        game.create_game();
+
+        assert_eq!(game.state() as u8, result.state as u8);
+        assert!(client.play(GameAction::HitAsDealer).is_err());
+
+        if result.state == SecureGameState::WaitingForPlayer {
+            assert!(client.new_hand().is_err());
+            assert!(client.play(GameAction::Decide).is_err());
+
+            result = client.decide(false).unwrap();
+        }
+
+        assert!(is_over(result.state));
+        assert!(client.play(GameAction::HitAsPlayer).is_err());
+
+        drop(client);
+
+        server.join().unwrap();
+    }
+}
@@ -0,0 +1,396 @@
+use crate::playing_card::{PlayingCard, Suit, RANK_MASK, SUIT_SHIFT};
+use crate::tfhe_values::FheCard;
+
+use tfhe::prelude::{
+    CastFrom, FheDecrypt, FheEq, FheMax, FheMin, FheTrivialEncrypt, IfThenElse, ScalarIfThenElse,
+};
+use tfhe::{ClientKey, FheBool, FheUint8};
+
+pub struct EncryptedSideBets {
+    pub perfect_pairs: FheUint8,
+    pub twenty_one_plus_three: FheUint8,
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum PerfectPairs {
+    Lose = 0,
+    MixedPair = 1,
+    ColoredPair = 2,
+    PerfectPair = 3,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PerfectPairsPayouts {
+    pub colored_pair: u32,
+    pub mixed_pair: u32,
+    pub perfect_pair: u32,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct SideBetPayouts {
+    pub perfect_pairs: PerfectPairsPayouts,
+    pub twenty_one_plus_three: TwentyOnePlusThreePayouts,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SideBets {
+    pub perfect_pairs: PerfectPairs,
+    pub perfect_pairs_payout: u32,
+    pub twenty_one_plus_three: TwentyOnePlusThree,
+    pub twenty_one_plus_three_payout: u32,
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum TwentyOnePlusThree {
+    Lose = 0,
+    Flush = 1,
+    Straight = 2,
+    ThreeOfAKind = 3,
+    StraightFlush = 4,
+    SuitedTrips = 5,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TwentyOnePlusThreePayouts {
+    pub flush: u32,
+    pub straight: u32,
+    pub straight_flush: u32,
+    pub suited_trips: u32,
+    pub three_of_a_kind: u32,
+}
+
+pub trait PayoutTable<Class> {
+    fn payout(&self, class: Class) -> u32;
+}
+
+impl EncryptedSideBets {
+    pub fn decrypt(&self, key: &ClientKey, payouts: &SideBetPayouts) -> SideBets {
+        let perfect_pairs: u8 = self.perfect_pairs.decrypt(key);
+        let twenty_one_plus_three: u8 = self.twenty_one_plus_three.decrypt(key);
+
+        payouts.settle(
+            PerfectPairs::try_from(perfect_pairs).unwrap(),
+            TwentyOnePlusThree::try_from(twenty_one_plus_three).unwrap(),
+        )
+    }
+}
+
+impl TryFrom<u8> for PerfectPairs {
+    type Error = u8;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(PerfectPairs::Lose),
+            1 => Ok(PerfectPairs::MixedPair),
+            2 => Ok(PerfectPairs::ColoredPair),
+            3 => Ok(PerfectPairs::PerfectPair),
+            _ => Err(value),
+        }
+    }
+}
+
+impl Default for PerfectPairsPayouts {
+    fn default() -> Self {
+        Self {
+            colored_pair: 12,
+            mixed_pair: 6,
+            perfect_pair: 25,
+        }
+    }
+}
+
+impl PayoutTable<PerfectPairs> for PerfectPairsPayouts {
+    fn payout(&self, class: PerfectPairs) -> u32 {
+        match class {
+            PerfectPairs::Lose => 0,
+            PerfectPairs::MixedPair => self.mixed_pair,
+            PerfectPairs::ColoredPair => self.colored_pair,
+            PerfectPairs::PerfectPair => self.perfect_pair,
+        }
+    }
+}
+
+impl SideBetPayouts {
+    pub fn settle(
+        &self,
+        perfect_pairs: PerfectPairs,
+        twenty_one_plus_three: TwentyOnePlusThree,
+    ) -> SideBets {
+        SideBets {
+            perfect_pairs,
+            perfect_pairs_payout: self.perfect_pairs.payout(perfect_pairs),
+            twenty_one_plus_three,
+            twenty_one_plus_three_payout: self.twenty_one_plus_three.payout(twenty_one_plus_three),
+        }
+    }
+}
+
+impl SideBets {
+    pub fn evaluate(cards_for_player: &[u8], dealer_up_card: u8, payouts: &SideBetPayouts) -> Self {
+        payouts.settle(
+            rate_perfect_pairs(cards_for_player[0], cards_for_player[1]),
+            rate_twenty_one_plus_three([cards_for_player[0], cards_for_player[1], dealer_up_card]),
+        )
+    }
+}
+
+impl TryFrom<u8> for TwentyOnePlusThree {
+    type Error = u8;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(TwentyOnePlusThree::Lose),
+            1 => Ok(TwentyOnePlusThree::Flush),
+            2 => Ok(TwentyOnePlusThree::Straight),
+            3 => Ok(TwentyOnePlusThree::ThreeOfAKind),
+            4 => Ok(TwentyOnePlusThree::StraightFlush),
+            5 => Ok(TwentyOnePlusThree::SuitedTrips),
+            _ => Err(value),
+        }
+    }
+}
+
+impl Default for TwentyOnePlusThreePayouts {
+    fn default() -> Self {
+        Self {
+            flush: 5,
+            straight: 10,
+            straight_flush: 40,
+            suited_trips: 100,
+            three_of_a_kind: 30,
+        }
+    }
+}
+
+impl PayoutTable<TwentyOnePlusThree> for TwentyOnePlusThreePayouts {
+    fn payout(&self, class: TwentyOnePlusThree) -> u32 {
+        match class {
+            TwentyOnePlusThree::Lose => 0,
+            TwentyOnePlusThree::Flush => self.flush,
+            TwentyOnePlusThree::Straight => self.straight,
+            TwentyOnePlusThree::ThreeOfAKind => self.three_of_a_kind,
+            TwentyOnePlusThree::StraightFlush => self.straight_flush,
+            TwentyOnePlusThree::SuitedTrips => self.suited_trips,
+        }
+    }
+}
+
+fn color_of(suit: &FheCard) -> FheCard {
+    (suit ^ (suit >> 1u8)) & 1u8
+}
+
+fn is_red(suit: Suit) -> bool {
+    matches!(suit, Suit::Diamonds | Suit::Hearts)
+}
+
+pub fn rate_encrypted_perfect_pairs(first: &FheCard, second: &FheCard) -> FheUint8 {
+    let (suit_for_first, suit_for_second) = (first >> SUIT_SHIFT, second >> SUIT_SHIFT);
+
+    let same_rank = (first & RANK_MASK).eq(&(second & RANK_MASK));
+    let same_color = color_of(&suit_for_first).eq(&color_of(&suit_for_second));
+    let same_suit = suit_for_first.eq(&suit_for_second);
+
+    let class = FheUint8::cast_from(same_color) + FheUint8::cast_from(same_suit) + 1;
+
+    same_rank.scalar_select(&class, 0u8)
+}
+
+pub fn rate_encrypted_twenty_one_plus_three(cards: [&FheCard; 3]) -> FheUint8 {
+    let ranks = cards.map(|card| card & RANK_MASK);
+    let suits = cards.map(|card| card >> SUIT_SHIFT);
+
+    let flush = suits[0].eq(&suits[1]) & suits[1].eq(&suits[2]);
+    let pairs = [
+        ranks[0].eq(&ranks[1]),
+        ranks[1].eq(&ranks[2]),
+        ranks[0].eq(&ranks[2]),
+    ];
+    let three_of_a_kind = pairs[0].clone() & pairs[1].clone();
+    let distinct = !(pairs[0].clone() | pairs[1].clone() | pairs[2].clone());
+
+    let high = ranks[0].max(&ranks[1]).max(&ranks[2]);
+    let low = ranks[0].min(&ranks[1]).min(&ranks[2]);
+    let sum = &ranks[0] + &ranks[1] + &ranks[2];
+
+    let straight = distinct & ((&high - &low).eq(2) | (high.eq(14) & low.eq(2) & sum.eq(19)));
+
+    let classes: [(FheBool, TwentyOnePlusThree); 5] = [
+        (
+            three_of_a_kind.clone() & flush.clone(),
+            TwentyOnePlusThree::SuitedTrips,
+        ),
+        (
+            straight.clone() & flush.clone(),
+            TwentyOnePlusThree::StraightFlush,
+        ),
+        (three_of_a_kind, TwentyOnePlusThree::ThreeOfAKind),
+        (straight, TwentyOnePlusThree::Straight),
+        (flush, TwentyOnePlusThree::Flush),
+    ];
+
+    classes.into_iter().rev().fold(
+        FheUint8::encrypt_trivial(TwentyOnePlusThree::Lose as u8),
+        |class, (condition, hand)| condition.select(&FheUint8::encrypt_trivial(hand as u8), &class),
+    )
+}
+
+pub fn rate_perfect_pairs(first: u8, second: u8) -> PerfectPairs {
+    let (first, second) = (PlayingCard::decode(first), PlayingCard::decode(second));
+
+    if first.rank != second.rank {
+        PerfectPairs::Lose
+    } else if first.suit == second.suit {
+        PerfectPairs::PerfectPair
+    } else if is_red(first.suit) == is_red(second.suit) {
+        PerfectPairs::ColoredPair
+    } else {
+        PerfectPairs::MixedPair
+    }
+}
+
+pub fn rate_twenty_one_plus_three(cards: [u8; 3]) -> TwentyOnePlusThree {
+    let cards = cards.map(PlayingCard::decode);
+
+    let mut ranks = cards.map(|card| card.rank);
+
+    ranks.sort();
+
+    let flush = cards.iter().all(|card| card.suit == cards[0].suit);
+    let three_of_a_kind = ranks[0] == ranks[2];
+    let distinct = ranks[0] != ranks[1] && ranks[1] != ranks[2];
+    let straight = distinct && (ranks[2] - ranks[0] == 2 || ranks == [2, 3, 14]);
+
+    if three_of_a_kind && flush {
+        TwentyOnePlusThree::SuitedTrips
+    } else if straight && flush {
+        TwentyOnePlusThree::StraightFlush
+    } else if three_of_a_kind {
+        TwentyOnePlusThree::ThreeOfAKind
+    } else if straight {
+        TwentyOnePlusThree::Straight
+    } else if flush {
+        TwentyOnePlusThree::Flush
+    } else {
+        TwentyOnePlusThree::Lose
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::tfhe_keys::initialize_keys;
+    use crate::tfhe_values::encrypt_playing_card;
+
+    fn card(rank: u8, suit: Suit) -> u8 {
+        PlayingCard::new(rank, suit).encode()
+    }
+
+    #[test]
+    fn rate_encrypted_side_bets() {
+        let (client_key, _) = initialize_keys();
+
+        let cards = [
+            PlayingCard::new(7, Suit::Hearts),
+            PlayingCard::new(7, Suit::Diamonds),
+            PlayingCard::new(7, Suit::Hearts),
+        ]
+        .map(|card| encrypt_playing_card(&client_key, &card));
+
+        let side_bets = EncryptedSideBets {
+            perfect_pairs: rate_encrypted_perfect_pairs(&cards[0], &cards[1]),
+            twenty_one_plus_three: rate_encrypted_twenty_one_plus_three([
+                &cards[0], &cards[1], &cards[2],
+            ]),
+        };
+
+        assert_eq!(
+            SideBets {
+                perfect_pairs: PerfectPairs::ColoredPair,
+                perfect_pairs_payout: 12,
+                twenty_one_plus_three: TwentyOnePlusThree::ThreeOfAKind,
+                twenty_one_plus_three_payout: 30,
+            },
+            side_bets.decrypt(&client_key, &SideBetPayouts::default())
+        );
+    }
+
+    #[test]
+    fn rate_side_bets() {
+        let (clubs, hearts, spades) = (Suit::Clubs, Suit::Hearts, Suit::Spades);
+
+        let pairs = [
+            ((9, clubs), (8, clubs), PerfectPairs::Lose),
+            ((9, clubs), (9, hearts), PerfectPairs::MixedPair),
+            ((9, clubs), (9, spades), PerfectPairs::ColoredPair),
+            ((9, clubs), (9, clubs), PerfectPairs::PerfectPair),
+        ];
+
+        for ((rank, suit), (other_rank, other_suit), class) in pairs {
+            assert_eq!(
+                class,
+                rate_perfect_pairs(card(rank, suit), card(other_rank, other_suit))
+            );
+        }
+
+        let hands = [
+            (
+                [(2, clubs), (9, hearts), (5, clubs)],
+                TwentyOnePlusThree::Lose,
+            ),
+            (
+                [(2, clubs), (9, clubs), (5, clubs)],
+                TwentyOnePlusThree::Flush,
+            ),
+            (
+                [(14, clubs), (2, hearts), (3, clubs)],
+                TwentyOnePlusThree::Straight,
+            ),
+            (
+                [(12, clubs), (13, hearts), (14, clubs)],
+                TwentyOnePlusThree::Straight,
+            ),
+            (
+                [(13, clubs), (14, hearts), (2, clubs)],
+                TwentyOnePlusThree::Lose,
+            ),
+            (
+                [(4, clubs), (4, hearts), (4, clubs)],
+                TwentyOnePlusThree::ThreeOfAKind,
+            ),
+            (
+                [(5, spades), (6, spades), (4, spades)],
+                TwentyOnePlusThree::StraightFlush,
+            ),
+            (
+                [(4, clubs), (4, clubs), (4, clubs)],
+                TwentyOnePlusThree::SuitedTrips,
+            ),
+        ];
+
+        for (cards, class) in hands {
+            assert_eq!(
+                class,
+                rate_twenty_one_plus_three(cards.map(|(rank, suit)| card(rank, suit)))
+            );
+        }
+
+        let payouts = SideBetPayouts {
+            perfect_pairs: PerfectPairsPayouts {
+                perfect_pair: 30,
+                ..PerfectPairsPayouts::default()
+            },
+            ..SideBetPayouts::default()
+        };
+
+        assert_eq!(
+            SideBets {
+                perfect_pairs: PerfectPairs::PerfectPair,
+                perfect_pairs_payout: 30,
+                twenty_one_plus_three: TwentyOnePlusThree::Flush,
+                twenty_one_plus_three_payout: 5,
+            },
+            SideBets::evaluate(&[card(9, clubs), card(9, clubs)], card(2, clubs), &payouts)
+        );
+    }
+}
@@ -0,0 +1,303 @@
+use crate::card_counting::{BetSpread, CardCounter};
+use crate::game_rules::RuleSet;
+use crate::game_transcript::GameAction;
+use crate::hand_history::{payout, HandRecord};
+use crate::naive_game::{NaiveGame, NaiveGameState};
+use crate::strategy::Strategy;
+
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+
+const PENETRATION: f64 = 0.75;
+const Z_95: f64 = 1.96;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct CountingReport {
+    pub flat: SimulationReport,
+    pub spread: SimulationReport,
+    pub wagered: f64,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct SimulationReport {
+    pub hands: u64,
+    pub losses: u64,
+    pub payout: f64,
+    pub payout_squared: f64,
+    pub pushes: u64,
+    pub wins: u64,
+}
+
+impl CountingReport {
+    pub fn advantage(&self) -> f64 {
+        self.spread_edge() - self.flat.expected_value()
+    }
+
+    fn merge(self, other: Self) -> Self {
+        Self {
+            flat: self.flat.merge(other.flat),
+            spread: self.spread.merge(other.spread),
+            wagered: self.wagered + other.wagered,
+        }
+    }
+
+    fn record(payout: f64, bet: f64) -> Self {
+        Self {
+            flat: SimulationReport::record(payout),
+            spread: SimulationReport::record(payout * bet),
+            wagered: bet,
+        }
+    }
+
+    pub fn spread_edge(&self) -> f64 {
+        self.spread.payout / self.wagered
+    }
+}
+
+impl SimulationReport {
+    pub fn confidence_interval(&self) -> (f64, f64) {
+        let margin = Z_95 * self.standard_error();
+
+        (
+            self.expected_value() - margin,
+            self.expected_value() + margin,
+        )
+    }
+
+    pub fn expected_value(&self) -> f64 {
+        self.payout / self.hands as f64
+    }
+
+    pub fn loss_rate(&self) -> f64 {
+        self.losses as f64 / self.hands as f64
+    }
+
+    fn merge(self, other: Self) -> Self {
+        Self {
+            hands: self.hands + other.hands,
+            losses: self.losses + other.losses,
+            payout: self.payout + other.payout,
+            payout_squared: self.payout_squared + other.payout_squared,
+            pushes: self.pushes + other.pushes,
+            wins: self.wins + other.wins,
+        }
+    }
+
+    pub fn push_rate(&self) -> f64 {
+        self.pushes as f64 / self.hands as f64
+    }
+
+    fn record(payout: f64) -> Self {
+        Self {
+            hands: 1,
+            losses: (payout < 0.0) as u64,
+            payout,
+            payout_squared: payout * payout,
+            pushes: (payout == 0.0) as u64,
+            wins: (payout > 0.0) as u64,
+        }
+    }
+
+    pub fn standard_error(&self) -> f64 {
+        let hands = self.hands as f64;
+        let variance = self.payout_squared / hands - self.expected_value().powi(2);
+
+        (variance / hands).sqrt()
+    }
+
+    pub fn win_rate(&self) -> f64 {
+        self.wins as f64 / self.hands as f64
+    }
+}
+
+fn play_game(strategy: &impl Strategy, game: &mut NaiveGame) -> f64 {
+    let rules = game.rules();
+
+    game.create_game();
+
+    while game.state() == NaiveGameState::WaitingForPlayer {
+        let dealer_up_card = game.cards_for_dealer()[0];
+
+        match strategy.decide(&rules, game.cards_for_player(), dealer_up_card) {
+            GameAction::HitAsPlayer => game.hit_as_player(),
+            _ => game.stand(),
+        }
+    }
+
+    while game.state() == NaiveGameState::WaitingForDealer {
+        game.hit_as_dealer();
+    }
+
+    payout(&rules, game.actions(), game.state())
+}
+
+pub fn play_hand(strategy: &impl Strategy, rules: RuleSet, seed: u64) -> f64 {
+    let mut game = NaiveGame::with_rules(seed, rules);
+
+    play_game(strategy, &mut game)
+}
+
+fn play_shoe(
+    strategy: &impl Strategy,
+    mut game: NaiveGame,
+    counter: &CardCounter,
+    spread: &BetSpread,
+) -> CountingReport {
+    let mut counter = counter.clone();
+    let mut report = CountingReport::default();
+
+    let cut_card = (counter.decks() as f64 * 52.0 * PENETRATION) as usize;
+
+    counter.reset();
+
+    while counter.cards_seen() < cut_card {
+        let bet = spread.bet(counter.betting_count());
+        let payout = play_game(strategy, &mut game);
+
+        counter.observe_all(game.cards_for_player());
+        counter.observe_all(game.cards_for_dealer());
+
+        report = report.merge(CountingReport::record(payout, bet));
+
+        game.next_hand();
+    }
+
+    report
+}
+
+pub fn record_hands(
+    strategy: &impl Strategy,
+    rules: RuleSet,
+    hands: u64,
+    seed: u64,
+) -> Vec<HandRecord> {
+    (0..hands)
+        .map(|hand| {
+            let mut game = NaiveGame::with_rules(seed.wrapping_add(hand), rules);
+
+            play_game(strategy, &mut game);
+
+            game.hand_record(hand)
+        })
+        .collect()
+}
+
+pub fn simulate(
+    strategy: &(impl Strategy + Sync),
+    rules: RuleSet,
+    hands: u64,
+    seed: u64,
+) -> SimulationReport {
+    (0..hands)
+        .into_par_iter()
+        .map(|hand| SimulationReport::record(play_hand(strategy, rules, seed.wrapping_add(hand))))
+        .reduce(SimulationReport::default, SimulationReport::merge)
+}
+
+pub fn simulate_counting(
+    strategy: &(impl Strategy + Sync),
+    rules: RuleSet,
+    counter: &CardCounter,
+    spread: &BetSpread,
+    shoes: u64,
+    seed: u64,
+) -> CountingReport {
+    (0..shoes)
+        .into_par_iter()
+        .map(|shoe| {
+            let game = NaiveGame::with_rules(seed.wrapping_add(shoe), rules);
+
+            play_shoe(strategy, game, counter, spread)
+        })
+        .reduce(CountingReport::default, CountingReport::merge)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::card_counting::CountingSystem;
+    use crate::strategy::{BasicStrategy, MimicTheDealer};
+    use crate::tfhe_keys::initialize_keys;
+    use crate::tfhe_values::generate_card;
+
+    use tfhe::prelude::FheDecrypt;
+
+    const GENERATED_CARDS: u128 = 52;
+    const MIMIC_THE_DEALER_EDGE: f64 = 0.056;
+    const PUBLISHED_EDGE_TOLERANCE: f64 = 0.01;
+
+    fn published_rules() -> RuleSet {
+        RuleSet {
+            natural_pays: 1.5,
+            soft_aces: true,
+            ..RuleSet::default()
+        }
+    }
+
+    #[test]
+    fn count_finite_shoes_only() {
+        let (client_key, _) = initialize_keys();
+
+        let rules = RuleSet {
+            decks: Some(6),
+            ..RuleSet::default()
+        };
+        let counter = CardCounter::new(CountingSystem::HiLo, 6);
+        let spread = BetSpread::default();
+
+        let mut generated = NaiveGame::new(0);
+
+        generated.plant_deck(
+            &(0..GENERATED_CARDS)
+                .map(|seed| generate_card(seed).decrypt(&client_key))
+                .collect::<Vec<u8>>(),
+        );
+
+        let shoe = simulate_counting(&BasicStrategy, rules, &counter, &spread, 10_000, 0);
+        let infinite = simulate_counting(
+            &BasicStrategy,
+            RuleSet::default(),
+            &counter,
+            &spread,
+            10_000,
+            0,
+        )
+        .merge(play_shoe(&BasicStrategy, generated, &counter, &spread));
+
+        assert!(shoe.advantage() > 0.0);
+        assert!(infinite.advantage().abs() < shoe.advantage());
+    }
+
+    #[test]
+    fn simulate_basic_strategy() {
+        let report = simulate(&BasicStrategy, published_rules(), 10_000, 0);
+
+        let (lower, upper) = report.confidence_interval();
+
+        assert_eq!(10_000, report.hands);
+        assert_eq!(report.hands, report.wins + report.losses + report.pushes);
+        assert!(-MIMIC_THE_DEALER_EDGE < lower && upper < 0.0);
+        assert!((report.win_rate() + report.loss_rate() + report.push_rate() - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn simulate_deterministically() {
+        let rules = RuleSet {
+            decks: Some(6),
+            natural_pays: 1.5,
+            ..RuleSet::default()
+        };
+
+        assert_eq!(
+            simulate(&MimicTheDealer, rules, 1_000, 7),
+            simulate(&MimicTheDealer, rules, 1_000, 7)
+        );
+    }
+
+    #[test]
+    fn simulate_mimic_the_dealer() {
+        let report = simulate(&MimicTheDealer, published_rules(), 200_000, 0);
+
+        assert!((report.expected_value() + MIMIC_THE_DEALER_EDGE).abs() < PUBLISHED_EDGE_TOLERANCE);
+    }
+}
@@ -0,0 +1,103 @@
+use crate::game_rules::RuleSet;
+use crate::game_transcript::GameAction;
+
+pub trait Strategy {
+    fn decide(&self, rules: &RuleSet, cards_for_player: &[u8], dealer_up_card: u8) -> GameAction;
+}
+
+pub struct BasicStrategy;
+
+pub struct MimicTheDealer;
+
+pub struct NeverBust;
+
+impl Strategy for BasicStrategy {
+    fn decide(&self, rules: &RuleSet, cards_for_player: &[u8], dealer_up_card: u8) -> GameAction {
+        let points_for_player = rules.rate_cards(cards_for_player);
+        let points_for_dealer = rules.rate_card(dealer_up_card);
+
+        let stand = match points_for_player {
+            0..=11 => false,
+            12 => (4..=6).contains(&points_for_dealer),
+            13..=16 => points_for_dealer <= 6,
+            _ => true,
+        };
+
+        if stand {
+            GameAction::Stand
+        } else {
+            GameAction::HitAsPlayer
+        }
+    }
+}
+
+impl Strategy for MimicTheDealer {
+    fn decide(&self, rules: &RuleSet, cards_for_player: &[u8], _: u8) -> GameAction {
+        if rules.rate_cards(cards_for_player) < rules.dealer_stands_on {
+            GameAction::HitAsPlayer
+        } else {
+            GameAction::Stand
+        }
+    }
+}
+
+impl Strategy for NeverBust {
+    fn decide(&self, rules: &RuleSet, cards_for_player: &[u8], _: u8) -> GameAction {
+        if rules.rate_cards(cards_for_player) + rules.rate_card(14) <= 21 {
+            GameAction::HitAsPlayer
+        } else {
+            GameAction::Stand
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const K: u8 = 13;
+    const A: u8 = 14;
+
+    #[test]
+    fn basic_strategy() {
+        let rules = RuleSet::default();
+
+        assert_eq!(
+            GameAction::HitAsPlayer,
+            BasicStrategy.decide(&rules, &[6, 5], K)
+        );
+        assert_eq!(
+            GameAction::HitAsPlayer,
+            BasicStrategy.decide(&rules, &[8, 4], 3)
+        );
+        assert_eq!(GameAction::Stand, BasicStrategy.decide(&rules, &[8, 4], 5));
+        assert_eq!(GameAction::Stand, BasicStrategy.decide(&rules, &[9, 6], 6));
+        assert_eq!(
+            GameAction::HitAsPlayer,
+            BasicStrategy.decide(&rules, &[9, 6], A)
+        );
+        assert_eq!(GameAction::Stand, BasicStrategy.decide(&rules, &[K, 7], A));
+    }
+
+    #[test]
+    fn mimic_the_dealer() {
+        let rules = RuleSet::default();
+
+        assert_eq!(
+            GameAction::HitAsPlayer,
+            MimicTheDealer.decide(&rules, &[K, 6], 5)
+        );
+        assert_eq!(GameAction::Stand, MimicTheDealer.decide(&rules, &[K, 7], 5));
+    }
+
+    #[test]
+    fn never_bust() {
+        let rules = RuleSet::default();
+
+        assert_eq!(
+            GameAction::HitAsPlayer,
+            NeverBust.decide(&rules, &[6, 4], K)
+        );
+        assert_eq!(GameAction::Stand, NeverBust.decide(&rules, &[6, 5], K));
+    }
+}
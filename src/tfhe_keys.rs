@@ -9,6 +9,32 @@ use tfhe::{generate_keys, set_server_key, ClientKey, ConfigBuilder, ServerKey};
 const PATH_CLIENT: &str = ".tfhe/client.key";
 const PATH_SERVER: &str = ".tfhe/server.key";
 
+pub const SERVER_KEY_SIZE_LIMIT: u64 = 1 << 28;
+
+pub fn join_with_server_key<A, B, RA, RB>(server_key: Option<&ServerKey>, a: A, b: B) -> (RA, RB)
+where
+    A: FnOnce() -> RA + Send,
+    B: FnOnce() -> RB + Send,
+    RA: Send,
+    RB: Send,
+{
+    match server_key {
+        Some(server_key) => rayon::join(
+            || {
+                set_server_key(server_key.clone());
+
+                a()
+            },
+            || {
+                set_server_key(server_key.clone());
+
+                b()
+            },
+        ),
+        None => (a(), b()),
+    }
+}
+
 pub fn initialize_keys() -> (ClientKey, ServerKey) {
     let client_key: ClientKey;
     let server_key: ServerKey;
@@ -22,7 +48,7 @@ pub fn initialize_keys() -> (ClientKey, ServerKey) {
         let server_key_buffer = read(PATH_SERVER).unwrap();
         let server_key_cursor = Cursor::new(server_key_buffer);
 
-        server_key = safe_deserialize(server_key_cursor, u64::MAX).unwrap();
+        server_key = safe_deserialize(server_key_cursor, SERVER_KEY_SIZE_LIMIT).unwrap();
     } else {
         (client_key, server_key) = generate_keys(
             ConfigBuilder::with_custom_parameters(
@@ -40,11 +66,11 @@ pub fn initialize_keys() -> (ClientKey, ServerKey) {
         let mut server_key_buffer = Vec::new();
         let mut server_key_cursor = Cursor::new(&mut server_key_buffer);
 
-        safe_serialize(&server_key, &mut server_key_cursor, u64::MAX).unwrap();
+        safe_serialize(&server_key, &mut server_key_cursor, SERVER_KEY_SIZE_LIMIT).unwrap();
         write(PATH_SERVER, server_key_buffer).unwrap();
     }
 
     set_server_key(server_key.clone());
 
-    return (client_key, server_key);
+    (client_key, server_key)
 }
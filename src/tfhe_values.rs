@@ -1,16 +1,235 @@
+use crate::game_storage::StorageError;
+use crate::playing_card::{PlayingCard, RANK_MASK, SUITS, SUIT_SHIFT};
 use crate::secure_game::SecureGameState;
+use crate::secure_profile::{count, lt, select, Operation};
 
-use tfhe::prelude::{FheDecrypt, FheEncrypt};
-use tfhe::{ClientKey, FheUint8};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
 
-pub fn decrypt_cards(key: &ClientKey, cards: &[FheUint8]) -> Vec<u8> {
+use std::io::Cursor;
+use std::sync::OnceLock;
+
+use tfhe::named::Named;
+use tfhe::prelude::{CastFrom, FheDecrypt, FheEncrypt, FheOrd, FheTrivialEncrypt, IfThenElse};
+use tfhe::safe_serialization::{safe_deserialize, safe_serialize};
+use tfhe::{ClientKey, FheBool, FheUint8, MatchValues, Seed, Unversionize, Versionize};
+
+pub const CIPHERTEXT_SIZE_LIMIT: u64 = 1 << 17;
+pub const RANK_CANDIDATES: u128 = 8;
+
+#[cfg(not(feature = "narrow-integers"))]
+pub type FheCard = FheUint8;
+#[cfg(feature = "narrow-integers")]
+pub type FheCard = tfhe::FheUint6;
+
+pub type FhePoints = FheUint8;
+
+#[cfg(not(feature = "narrow-integers"))]
+pub type FheState = FheUint8;
+#[cfg(feature = "narrow-integers")]
+pub type FheState = tfhe::FheUint4;
+
+pub struct TrivialStates(Vec<FheState>);
+
+pub fn card_size(card: &FheCard) -> usize {
+    static CARD_SIZE: OnceLock<usize> = OnceLock::new();
+
+    *CARD_SIZE.get_or_init(|| serialize(card).len())
+}
+
+pub fn decrypt_cards(key: &ClientKey, cards: &[FheCard]) -> Vec<u8> {
     cards.iter().map(|card| card.decrypt(key)).collect()
 }
 
-pub fn encrypt_points(key: &ClientKey, points: u8) -> FheUint8 {
-    FheUint8::encrypt(points, key)
+pub fn decrypt_playing_card(key: &ClientKey, card: &FheCard) -> PlayingCard {
+    let card: u8 = card.decrypt(key);
+
+    PlayingCard::decode(card)
+}
+
+pub fn decrypt_playing_cards(key: &ClientKey, cards: &[FheCard]) -> Vec<PlayingCard> {
+    cards
+        .iter()
+        .map(|card| decrypt_playing_card(key, card))
+        .collect()
+}
+
+pub fn deserialize<T: DeserializeOwned + Named + Unversionize>(
+    buffer: &[u8],
+) -> Result<T, StorageError> {
+    safe_deserialize(Cursor::new(buffer), CIPHERTEXT_SIZE_LIMIT).map_err(|_| StorageError::Corrupt)
+}
+
+pub fn encrypt_card(key: &ClientKey, card: u8) -> FheCard {
+    count(Operation::Encryption, 1);
+
+    FheCard::encrypt(card, key)
+}
+
+pub fn encrypt_decision(key: &ClientKey, hit: bool) -> FheBool {
+    count(Operation::Encryption, 1);
+
+    FheBool::encrypt(hit, key)
+}
+
+pub fn encrypt_playing_card(key: &ClientKey, card: &PlayingCard) -> FheCard {
+    count(Operation::Encryption, 1);
+
+    FheCard::encrypt(card.encode(), key)
+}
+
+pub fn encrypt_points(key: &ClientKey, points: u8) -> FhePoints {
+    count(Operation::Encryption, 1);
+
+    FhePoints::encrypt(points, key)
+}
+
+pub fn generate_card(seed: u128) -> FheCard {
+    let seed = seed.wrapping_mul(RANK_CANDIDATES + 1);
+
+    let suit = FheCard::generate_oblivious_pseudo_random_bounded(Seed(seed), 2);
+    let rank = (1..=RANK_CANDIDATES).fold(trivial_card(0), |rank, candidate| {
+        let candidate = FheCard::generate_oblivious_pseudo_random_bounded(
+            Seed(seed.wrapping_add(candidate)),
+            4,
+        );
+
+        select(&lt(&candidate, 13), &candidate, &rank)
+    });
+
+    suit * (1 << SUIT_SHIFT) + rank + 2
+}
+
+pub fn points_size(points: &FhePoints) -> usize {
+    static POINTS_SIZE: OnceLock<usize> = OnceLock::new();
+
+    *POINTS_SIZE.get_or_init(|| serialize(points).len())
 }
 
-pub fn encrypt_state(key: &ClientKey, state: SecureGameState) -> FheUint8 {
-    FheUint8::encrypt(state as u8, key)
+pub fn rate_card(card: &FheCard) -> FhePoints {
+    let points = MatchValues::new(
+        SUITS
+            .iter()
+            .flat_map(|&suit| (2u8..=14).map(move |rank| PlayingCard::new(rank, suit)))
+            .map(|card| {
+                (
+                    card.encode(),
+                    if card.rank == 14 {
+                        11
+                    } else {
+                        card.rank.min(10)
+                    },
+                )
+            })
+            .collect(),
+    )
+    .unwrap();
+
+    count(Operation::Lookup, 1);
+
+    card.match_value_or(&points, 0u8).unwrap()
+}
+
+pub fn rate_card_by_comparison(card: &FheCard) -> FhePoints {
+    let rank = card & RANK_MASK;
+
+    rank.lt(11).select(
+        &FhePoints::cast_from(rank.clone()),
+        &rank.lt(14).select(&trivial_points(10), &trivial_points(11)),
+    )
+}
+
+pub fn serialize<T: Named + Serialize + Versionize>(value: &T) -> Vec<u8> {
+    let mut buffer = Vec::new();
+
+    safe_serialize(value, &mut Cursor::new(&mut buffer), CIPHERTEXT_SIZE_LIMIT).unwrap();
+
+    buffer
+}
+
+pub fn state_size(state: &FheState) -> usize {
+    static STATE_SIZE: OnceLock<usize> = OnceLock::new();
+
+    *STATE_SIZE.get_or_init(|| serialize(state).len())
+}
+
+pub fn trivial_card(card: u8) -> FheCard {
+    FheCard::encrypt_trivial(card)
+}
+
+pub fn trivial_points(points: u8) -> FhePoints {
+    FhePoints::encrypt_trivial(points)
+}
+
+impl Default for TrivialStates {
+    fn default() -> Self {
+        Self(
+            (SecureGameState::Uninitialized as u8..=SecureGameState::WaitingForPlayer as u8)
+                .map(FheState::encrypt_trivial)
+                .collect(),
+        )
+    }
+}
+
+impl TrivialStates {
+    pub fn get(&self, state: SecureGameState) -> &FheState {
+        &self.0[state as usize]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::game_rules::RuleSet;
+    use crate::playing_card::rank;
+    use crate::tfhe_keys::initialize_keys;
+
+    const CHI_SQUARED_RANKS: f64 = 32.91;
+    const CHI_SQUARED_SUITS: f64 = 16.27;
+
+    fn chi_squared(counts: &[u32], expected: f64) -> f64 {
+        counts
+            .iter()
+            .map(|&count| (count as f64 - expected).powi(2) / expected)
+            .sum()
+    }
+
+    #[test]
+    fn generate_uniform_cards() {
+        let (client_key, _) = initialize_keys();
+
+        let cards: Vec<u8> = (0..208)
+            .map(|seed| generate_card(seed).decrypt(&client_key))
+            .collect();
+
+        let mut ranks = [0; 13];
+        let mut suits = [0; 4];
+
+        for &card in &cards {
+            ranks[rank(card) as usize - 2] += 1;
+            suits[(card >> SUIT_SHIFT) as usize] += 1;
+        }
+
+        assert!(chi_squared(&ranks, 16.0) < CHI_SQUARED_RANKS);
+        assert!(chi_squared(&suits, 52.0) < CHI_SQUARED_SUITS);
+    }
+
+    #[test]
+    fn rate_cards_by_lookup_table() {
+        let (client_key, _) = initialize_keys();
+
+        for suit in SUITS {
+            for rank in 2..=14 {
+                let card_value = PlayingCard::new(rank, suit).encode();
+                let card = encrypt_card(&client_key, card_value);
+
+                let by_lookup_table: u8 = rate_card(&card).decrypt(&client_key);
+                let by_comparison: u8 = rate_card_by_comparison(&card).decrypt(&client_key);
+
+                assert_eq!(by_comparison, by_lookup_table);
+                assert_eq!(RuleSet::default().rate_card(card_value), by_lookup_table);
+            }
+        }
+    }
 }
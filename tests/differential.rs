@@ -0,0 +1,100 @@
+use std::env;
+
+use blockjack::game_transcript::{ActionError, GameAction};
+use blockjack::naive_game::NaiveGame;
+use blockjack::playing_card::{PlayingCard, SUITS};
+use blockjack::secure_game::SecureGame;
+use blockjack::tfhe_keys::initialize_keys;
+use blockjack::tfhe_values::encrypt_decision;
+
+use proptest::prelude::*;
+use proptest::test_runner::{Config, TestRunner};
+
+use tfhe::ClientKey;
+
+const CASES: u32 = 16;
+const MAX_ACTIONS: usize = 4;
+
+fn action() -> impl Strategy<Value = (GameAction, bool)> {
+    (
+        prop_oneof![
+            Just(GameAction::Decide),
+            Just(GameAction::HitAsDealer),
+            Just(GameAction::HitAsPlayer),
+            Just(GameAction::Stand),
+        ],
+        any::<bool>(),
+    )
+}
+
+fn card() -> impl Strategy<Value = u8> {
+    (2u8..=14, 0..SUITS.len()).prop_map(|(rank, suit)| PlayingCard::new(rank, SUITS[suit]).encode())
+}
+
+fn cases() -> u32 {
+    env::var("BLOCKJACK_DIFFERENTIAL_CASES")
+        .ok()
+        .and_then(|cases| cases.parse().ok())
+        .unwrap_or(CASES)
+}
+
+fn script() -> impl Strategy<Value = (Vec<u8>, Vec<(GameAction, bool)>)> {
+    prop::collection::vec(action(), 0..=MAX_ACTIONS).prop_flat_map(|actions| {
+        let deck = prop::collection::vec(card(), actions.len() + 4);
+
+        (deck, Just(actions))
+    })
+}
+
+fn compare(
+    key: &ClientKey,
+    naive_game: &NaiveGame,
+    secure_game: &SecureGame,
+) -> Result<(), TestCaseError> {
+    prop_assert_eq!(
+        naive_game.cards_for_player(),
+        secure_game.cards_for_player().decrypt(key)
+    );
+    prop_assert_eq!(
+        naive_game.cards_for_dealer(),
+        secure_game.cards_for_dealer().decrypt(key)
+    );
+    prop_assert_eq!(naive_game.state() as u8, secure_game.state() as u8);
+
+    Ok(())
+}
+
+#[test]
+fn engines_agree_on_random_scripts() {
+    let (client_key, _) = initialize_keys();
+
+    let mut runner = TestRunner::new(Config::with_cases(cases()));
+
+    let result = runner.run(&script(), |(deck, actions)| {
+        let mut naive_game = NaiveGame::new(0);
+        let mut secure_game = SecureGame::new(&client_key);
+
+        naive_game.plant_deck(&deck);
+        secure_game.plant_deck(&deck);
+
+        for (action, hit) in [(GameAction::CreateGame, false)].into_iter().chain(actions) {
+            if action == GameAction::Decide {
+                prop_assert_eq!(Err(ActionError::DecisionRequired), naive_game.play(action));
+                prop_assert_eq!(Err(ActionError::DecisionRequired), secure_game.play(action));
+
+                naive_game.decide(hit);
+                secure_game.decide(&encrypt_decision(&client_key, hit));
+            } else {
+                prop_assert_eq!(naive_game.play(action), secure_game.play(action));
+            }
+
+            compare(&client_key, &naive_game, &secure_game)?;
+        }
+
+        Ok(())
+    });
+
+    if let Err(error) = result {
+        panic!("{}", error);
+    }
+}